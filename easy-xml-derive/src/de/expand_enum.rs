@@ -14,6 +14,37 @@ pub fn expand_derive_enum(
 
     let get_from_node = get_from_node(enum_name, data);
 
+    let text_variant = utils::text_variant(data);
+
+    // 其他文本交给text变体
+    let text_fallback = match text_variant {
+        Some(v) => {
+            let ident = &v.ident;
+            quote! {
+              _ => Ok(#enum_name::#ident(easy_xml::XmlDeserialize::deserialize(element)?)),
+            }
+        }
+        None => quote! {
+          _ => return Err(easy_xml::de::Error::Other("".to_string())),
+        },
+    };
+
+    // mixed字段中的文本只由text变体读取，不与单元变体的名称匹配
+    let mixed_text = match text_variant {
+        Some(v) => {
+            let ident = &v.ident;
+            quote! {
+              let element = easy_xml::XmlElement::Text(text.to_string());
+              Some(easy_xml::XmlDeserialize::deserialize(&element).map(#enum_name::#ident))
+            }
+        }
+        None => quote! {
+          None
+        },
+    };
+
+    let mixed_node_match = mixed_node_match(data);
+
     Ok(quote! {
 
       impl easy_xml::XmlDeserialize for #enum_name {
@@ -24,7 +55,7 @@ pub fn expand_derive_enum(
             match element {
                 easy_xml::XmlElement::Text(text) => match text.as_str() {
                     #get_from_text
-                    _ => return Err(easy_xml::de::Error::Other("".to_string())),
+                    #text_fallback
                 },
                 easy_xml::XmlElement::Node(node) => {
                   let node = &*node.borrow();
@@ -37,12 +68,33 @@ pub fn expand_derive_enum(
                 easy_xml::XmlElement::Whitespace(_) => {return Err(easy_xml::de::Error::Other("".to_string()))},
                 easy_xml::XmlElement::Comment(_) => {return Err(easy_xml::de::Error::Other("".to_string()))},
                 easy_xml::XmlElement::CData(_) => {return Err(easy_xml::de::Error::Other("".to_string()))},
+                easy_xml::XmlElement::Raw(_) => {return Err(easy_xml::de::Error::Other("".to_string()))},
             }
         }
+
+        fn matches_mixed_node(node: &easy_xml::XmlNode) -> bool {
+            let name = &node.name;
+            #mixed_node_match
+        }
+
+        fn deserialize_mixed_text(text: &str) -> Option<Result<Self, easy_xml::de::Error>> {
+            #mixed_text
+        }
       }
     })
 }
 
+// 节点名称与某个非text变体匹配
+fn mixed_node_match(data: &syn::DataEnum) -> TokenStream {
+    let matches = (&data.variants)
+        .into_iter()
+        .filter(|v| !Attributes::new(&v.attrs).text)
+        .map(|v| owned_name_match(&v.ident, &Attributes::new(&v.attrs)));
+    quote! {
+      false #(|| (#matches))*
+    }
+}
+
 fn get_from_text(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
     return (&data.variants)
         .into_iter()
@@ -69,6 +121,7 @@ fn get_from_text(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
 fn get_from_node(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
     let token: TokenStream = (&data.variants)
         .into_iter()
+        .filter(|v| !Attributes::new(&v.attrs).text)
         .map(|v| {
             let ident = &v.ident;
             let attrs = Attributes::new(&v.attrs);
@@ -105,7 +158,8 @@ fn get_from_node(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
                         .collect::<Vec<_>>();
 
                     for f in &fields {
-                        f.check()
+                        f.check();
+                        f.check_positional()
                     }
 
                     code_for_named_and_unnamed(false, enum_name, ident, fields)
@@ -163,6 +217,10 @@ fn code_for_named_and_unnamed(
 
     let code_for_children = utils::de_build_code_for_children(&fields);
 
+    let code_for_mixed = utils::de_build_code_for_mixed(&fields);
+
+    let code_for_comment = utils::de_build_code_for_comment(&fields);
+
     let var_rebind = utils::de_var_rebind(&fields);
 
     let var_collect = utils::de_var_collect(&fields);
@@ -191,6 +249,10 @@ fn code_for_named_and_unnamed(
 
       #code_for_children
 
+      #code_for_mixed
+
+      #code_for_comment
+
       #var_rebind
 
       #var_collect
@@ -8,11 +8,13 @@ pub fn expand_derive_struct(
     data: &syn::DataStruct,
 ) -> Result<TokenStream, String> {
     let name = &ast.ident;
+    let attrs = utils::Attributes::new(&ast.attrs);
 
     let fields = (&data.fields)
         .into_iter()
-        .map(|f| {
-            let f = utils::Field::from_struct(f);
+        .enumerate()
+        .map(|(i, f)| {
+            let f = utils::Field::from_struct(f, i as i32);
             return f;
         })
         .collect::<Vec<_>>();
@@ -21,6 +23,14 @@ pub fn expand_derive_struct(
         f.check()
     }
 
+    if attrs.transparent {
+        return Ok(expand_transparent(name, &fields));
+    }
+
+    for f in &fields {
+        f.check_positional()
+    }
+
     // //变量声明
     let code_for_declare = utils::de_build_code_for_declare(&fields);
 
@@ -33,10 +43,19 @@ pub fn expand_derive_struct(
 
     let code_for_children = utils::de_build_code_for_children(&fields);
 
+    let code_for_mixed = utils::de_build_code_for_mixed(&fields);
+
+    let code_for_comment = utils::de_build_code_for_comment(&fields);
+
     let var_rebind = utils::de_var_rebind(&fields);
 
     let var_collect = utils::de_var_collect(&fields);
 
+    let instance = match &data.fields {
+        syn::Fields::Unnamed(_) => quote! {#name(#var_collect)},
+        _ => quote! {#name{#var_collect}},
+    };
+
     Ok(quote! {
       impl easy_xml::XmlDeserialize for #name{
         fn deserialize(element: &easy_xml::XmlElement) -> Result<Self, easy_xml::de::Error>
@@ -58,6 +77,10 @@ pub fn expand_derive_struct(
                   #code_for_attribute
 
                   #code_for_children
+
+                  #code_for_mixed
+
+                  #code_for_comment
               }
               easy_xml::XmlElement::Text(text) => {}
               _ => {}
@@ -65,13 +88,35 @@ pub fn expand_derive_struct(
 
             #var_rebind
 
-            Ok(
-              #name{
-                #var_collect
-              }
-            )
+            Ok(#instance)
         }
       }
 
     })
 }
+
+// 将节点整个交给唯一的字段
+fn expand_transparent(name: &syn::Ident, fields: &Vec<utils::Field>) -> TokenStream {
+    let f = utils::transparent_field(fields);
+    let item = f.de_transparent_expr();
+    let absent = f.de_transparent_absent();
+    let member = f.member();
+    let instance = quote! {#name{#member: value}};
+    quote! {
+      impl easy_xml::XmlDeserialize for #name {
+        fn deserialize(element: &easy_xml::XmlElement) -> Result<Self, easy_xml::de::Error>
+        where
+            Self: Sized {
+            let value = #item;
+            Ok(#instance)
+        }
+
+        fn absent() -> Option<Self>
+        where
+            Self: Sized {
+            let value = #absent;
+            value.map(|value| #instance)
+        }
+      }
+    }
+}
@@ -11,9 +11,11 @@ pub fn expand_derive_enum(
 ) -> Result<TokenStream, String> {
     let enum_name = &ast.ident;
 
-    let code_for_text = build_code_for_text(enum_name, data);
+    let text_variant = utils::text_variant(data).map(|v| &v.ident);
 
-    let code_for_node = build_code_for_node(enum_name, data);
+    let code_for_text = build_code_for_text(enum_name, data, text_variant);
+
+    let code_for_node = build_code_for_node(enum_name, data, text_variant);
 
     let code_for_node_text = quote! {
       if node.borrow().name.local_name.len() != 0 {
@@ -46,7 +48,11 @@ pub fn expand_derive_enum(
     })
 }
 
-fn build_code_for_text(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
+fn build_code_for_text(
+    enum_name: &Ident,
+    data: &syn::DataEnum,
+    text_variant: Option<&Ident>,
+) -> TokenStream {
     let code: TokenStream = (&data.variants)
         .into_iter()
         .map(|v| {
@@ -75,6 +81,17 @@ fn build_code_for_text(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
                       #enum_name::#var_name{#vars} => {text.push_str(#var_name_str)}
                     }
                 }
+                syn::Fields::Unnamed(_) if text_variant == Some(var_name) => {
+                    quote! {
+                      #enum_name::#var_name(f_1) => {
+                        let mut value = easy_xml::XmlElement::Text(String::new());
+                        f_1.serialize(&mut value);
+                        if let easy_xml::XmlElement::Text(value) = value {
+                          text.push_str(value.as_str());
+                        }
+                      }
+                    }
+                }
                 syn::Fields::Unnamed(unnamed) => {
                     let vars: TokenStream = (&unnamed.unnamed)
                         .into_iter()
@@ -97,7 +114,11 @@ fn build_code_for_text(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
     }
 }
 
-fn build_code_for_node(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
+fn build_code_for_node(
+    enum_name: &Ident,
+    data: &syn::DataEnum,
+    text_variant: Option<&Ident>,
+) -> TokenStream {
     let code: TokenStream = (&data.variants)
         .into_iter()
         .map(|v| {
@@ -105,6 +126,16 @@ fn build_code_for_node(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
             let attrs = Attributes::new(&v.attrs);
 
             match &v.fields {
+                // 文本变体不设置节点名称，直接写入文本
+                syn::Fields::Unnamed(_) if text_variant == Some(var_name) => {
+                    return quote! {
+                      #enum_name::#var_name(f_1) => {
+                        let mut value = easy_xml::XmlElement::Text(String::new());
+                        f_1.serialize(&mut value);
+                        node.borrow_mut().elements.push(value);
+                      },
+                    };
+                }
                 syn::Fields::Named(named) => {
                     let fields = (&named.named)
                         .into_iter()
@@ -134,6 +165,9 @@ fn build_code_for_node(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
                             return f;
                         })
                         .collect::<Vec<_>>();
+                    for f in &fields {
+                        f.check_positional()
+                    }
                     let vars: TokenStream = utils::se_build_code_for_fields(&fields);
 
                     let code_variant = utils::se_build_code_for_set_tag(&v.ident, &attrs);
@@ -167,6 +201,8 @@ fn build_code_for_node(enum_name: &Ident, data: &syn::DataEnum) -> TokenStream {
 }
 
 fn code_for_named_and_unnamed(fields: &Vec<Field>) -> TokenStream {
+    let code_for_comment = utils::se_build_code_for_comment(fields);
+
     let code_for_text = utils::se_build_code_for_text(&fields);
 
     let code_for_flatten = utils::se_build_code_for_flatten(&fields);
@@ -174,13 +210,19 @@ fn code_for_named_and_unnamed(fields: &Vec<Field>) -> TokenStream {
     let code_for_attribute = utils::se_build_code_for_attribute(&fields);
 
     let code_for_node = utils::se_build_code_for_node(&fields);
+
+    let code_for_mixed = utils::se_build_code_for_mixed(fields);
     quote! {
+      #code_for_comment
+
       #code_for_text
 
       #code_for_attribute
 
       #code_for_node
 
+      #code_for_mixed
+
       #code_for_flatten
     }
 }
@@ -12,8 +12,9 @@ pub fn expand_derive_struct(
 
     let fields = (&data.fields)
         .into_iter()
-        .map(|f| {
-            let f = Field::from_struct(f);
+        .enumerate()
+        .map(|(i, f)| {
+            let f = Field::from_struct(f, i as i32);
             return f;
         })
         .collect::<Vec<_>>();
@@ -22,27 +23,44 @@ pub fn expand_derive_struct(
         f.check()
     }
 
+    if attrs.transparent {
+        return Ok(expand_transparent(struct_name, &fields));
+    }
+
+    for f in &fields {
+        f.check_positional()
+    }
+
     let code_text_node: TokenStream = (&fields)
         .into_iter()
-        .filter(|f| f.attrs.skip == false)
+        .filter(|f| {
+            !f.attrs.skip_serializing
+                && !f.attrs.other_elements
+                && !f.attrs.other_attributes
+                && !f.attrs.comment
+                && f.attrs.map.is_none()
+        })
         .map(|f| {
             let var = f.field_name();
-            if (&f.ty).has_vec() {
+            let code = if f.is_repeated() {
+                let items = f.se_repeated_items();
+                let serialize = f.se_item_stmt(quote! {item}, quote! {element});
                 quote! {
-                  for item in &#var {
-                    item.serialize(element)
+                  for item in #items {
+                    #serialize
                   }
                 }
             } else {
-                quote! {
-                  #var.serialize(element);
-                }
-            }
+                f.se_item_stmt(var, quote! {element})
+            };
+            f.se_guard(code)
         })
         .collect();
 
     let code_for_root = utils::se_build_code_for_root(&ast.ident, &attrs);
 
+    let code_for_comment = utils::se_build_code_for_comment(&fields);
+
     let code_for_text = utils::se_build_code_for_text(&fields);
 
     let code_for_flatten = utils::se_build_code_for_flatten(&fields);
@@ -51,6 +69,8 @@ pub fn expand_derive_struct(
 
     let code_for_node = utils::se_build_code_for_node(&fields);
 
+    let code_for_mixed = utils::se_build_code_for_mixed(&fields);
+
     Ok(quote! {
       impl easy_xml::XmlSerialize for #struct_name {
         fn serialize(&self, element: &mut easy_xml::XmlElement)
@@ -66,11 +86,15 @@ pub fn expand_derive_struct(
 
                     #code_for_root
 
+                    #code_for_comment
+
                     #code_for_text
 
                     #code_for_attribute
 
                     #code_for_node
+
+                    #code_for_mixed
                 }
                 _ => {}
             }
@@ -79,3 +103,24 @@ pub fn expand_derive_struct(
       }
     })
 }
+
+// 将节点整个交给唯一的字段
+fn expand_transparent(struct_name: &syn::Ident, fields: &Vec<Field>) -> TokenStream {
+    let f = utils::transparent_field(fields);
+    let serialize = f.se_transparent_stmt();
+    let present = f.se_present();
+    quote! {
+      impl easy_xml::XmlSerialize for #struct_name {
+        fn serialize(&self, element: &mut easy_xml::XmlElement)
+        where
+            Self: Sized,
+        {
+            #serialize
+        }
+
+        fn is_absent(&self) -> bool {
+            !(#present)
+        }
+      }
+    }
+}
@@ -13,44 +13,6 @@ impl TypeWapper {
         return TypeWapper { ty: ty.clone() };
     }
 
-    pub fn get_ident(&self) -> Ident {
-        match &self.ty {
-            syn::Type::Path(ref path) => {
-                for i in &path.path.segments {
-                    return i.ident.clone();
-                }
-            }
-            _ => {
-                panic!("");
-            }
-        };
-        panic!("");
-    }
-
-    pub fn full_path(&self) -> String {
-        match &self.ty {
-            syn::Type::Path(ref path) => {
-                let paths = &path.path.segments;
-
-                let mut full_path = String::new();
-
-                let mut i = 0;
-                for p in paths.iter() {
-                    full_path.push_str(p.ident.to_string().as_str());
-                    if i < paths.len() - 1 {
-                        full_path.push_str("::");
-                    }
-                    i += 1;
-                }
-
-                return full_path;
-            }
-            _ => {
-                panic!("");
-            }
-        };
-    }
-
     pub fn next_type(&self) -> Option<Self> {
         match &self.ty {
             syn::Type::Path(ref path) => {
@@ -81,82 +43,96 @@ impl TypeWapper {
         panic!("");
     }
 
-    pub fn has_vec(&self) -> bool {
-        return self.has_subtype("Vec");
-    }
-
-    pub fn has_option(&self) -> bool {
-        return self.has_subtype("Option");
-    }
-
-    pub fn subtype_count(&self, subtype: &str) -> usize {
-        let mut count = 0;
-        self.type_for_each(|ty| {
-            if ty.get_ident().to_string().as_str() == subtype {
-                count += 1;
+    // 是否为内置的集合类型：Vec、VecDeque、HashSet、BTreeSet、SmallVec、Box<[T]>以及[T; N]
+    pub fn is_collection(&self) -> bool {
+        match &self.ty {
+            syn::Type::Array(_) => true,
+            syn::Type::Path(_) => {
+                if self.is("Box") {
+                    return matches!(
+                        self.next_type(),
+                        Some(TypeWapper {
+                            ty: syn::Type::Slice(_)
+                        })
+                    );
+                }
+                ["Vec", "VecDeque", "HashSet", "BTreeSet", "SmallVec"]
+                    .iter()
+                    .any(|ident| self.is(ident))
             }
-        });
-        return count;
-    }
-
-    pub fn has_subtype(&self, subtype: &str) -> bool {
-        return self.subtype_count(subtype) > 0;
+            _ => false,
+        }
     }
 
-    pub fn type_check(&self) {
-        let mut vec = 0;
-        let mut option = 0;
-
-        let mut path = String::new();
-
-        self.type_for_each(|ty| {
-            let ident = ty.get_ident().to_string();
-
-            path += ident.to_string().as_str();
-            path += ">";
-
-            match ident.as_str() {
-                "Option" => option += 1,
-                "Vec" => vec += 1,
-                _ => {}
-            }
-        });
-
-        if vec >= 2 {
-            panic!("Multi level nesting of Vec is not supported({})", path);
-        }
-        if option >= 2 {
-            panic!("Multi level nesting of Option is not supported({})", path);
-        }
-        if option == 1 && self.get_ident().to_string().as_str() != "Option" {
-            panic!("Option must be at the first level({})", path);
-        }
-        if vec == 1 && self.get_ident().to_string().as_str() != "Vec" {
-            panic!("Vec must be at the first level({})", path);
+    // 是否为内置的文本类型：数字、bool、char、字符串、路径和地址等，Option<T>和Box<T>按T判断
+    // 这类类型序列化时不会设置节点名称
+    pub fn is_scalar(&self) -> bool {
+        if self.is("Option") || self.is("Box") {
+            return match self.next_type() {
+                Some(inner) => inner.is_scalar(),
+                None => false,
+            };
         }
+        [
+            "bool",
+            "char",
+            "u8",
+            "u16",
+            "u32",
+            "u64",
+            "u128",
+            "usize",
+            "i8",
+            "i16",
+            "i32",
+            "i64",
+            "i128",
+            "isize",
+            "f32",
+            "f64",
+            "String",
+            "str",
+            "Cow",
+            "PathBuf",
+            "IpAddr",
+            "Ipv4Addr",
+            "Ipv6Addr",
+            "SocketAddr",
+            "SocketAddrV4",
+            "SocketAddrV6",
+        ]
+        .iter()
+        .any(|ident| self.is(ident))
+            || match &self.ty {
+                syn::Type::Path(path) => match path.path.segments.last() {
+                    Some(segment) => segment.ident.to_string().starts_with("NonZero"),
+                    None => false,
+                },
+                _ => false,
+            }
     }
 
-    // 子类型遍历
-    // Foreach Subtype
-    pub fn type_for_each<F>(&self, mut f: F)
-    where
-        F: FnMut(&Self),
-    {
-        f(self);
-
-        let mut next = self.next_type();
-        loop {
-            match next.as_ref() {
-                Some(ty) => {
-                    f(&ty);
-                    next = ty.next_type();
-                }
-                None => break,
-            }
+    // 最外层类型是否为ident，如Option<Vec<T>>的最外层为Option
+    pub fn is(&self, ident: &str) -> bool {
+        match &self.ty {
+            syn::Type::Path(path) => match path.path.segments.last() {
+                Some(segment) => segment.ident == ident,
+                None => false,
+            },
+            _ => false,
         }
     }
 }
 
+// map字段的键
+#[derive(Debug, PartialEq)]
+pub enum MapKey {
+    // 子节点的属性，map(key = "@name")
+    Attribute(String),
+    // 子节点的名称，map(key = tag)
+    Tag,
+}
+
 #[derive(Debug)]
 pub struct Attributes {
     pub flatten: bool,
@@ -167,9 +143,36 @@ pub struct Attributes {
     pub namespace: BTreeMap<String, String>,
     pub enums: bool,
     pub root: bool,
-    pub skip: bool,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    pub skip_serializing_if: Option<String>,
     pub to_text: bool,
     pub container: bool,
+    pub transparent: bool,
+    pub list: bool,
+    pub separator: Option<String>,
+    pub cdata: bool,
+    pub no_cdata: bool,
+    pub text_own: bool,
+    pub trim: bool,
+    pub normalize_space: bool,
+    pub mixed: bool,
+    pub other_elements: bool,
+    pub other_attributes: bool,
+    pub comment: bool,
+    pub map: Option<MapKey>,
+    pub item: Option<String>,
+    pub repeated: bool,
+    pub flag: bool,
+    pub bool_format: Option<String>,
+    pub float_format: Option<String>,
+    pub precision: Option<String>,
+    pub hex: bool,
+    pub plus_sign: bool,
+    pub from_str: bool,
+    pub display: bool,
+    pub deserialize_with: Option<String>,
+    pub serialize_with: Option<String>,
 }
 
 impl Attributes {
@@ -181,9 +184,36 @@ impl Attributes {
         let mut rename = None;
         let mut enums = false;
         let mut root = false;
-        let mut skip = false;
+        let mut skip_serializing = false;
+        let mut skip_deserializing = false;
+        let mut skip_serializing_if = None;
         let mut to_text = false;
         let mut container = false;
+        let mut transparent = false;
+        let mut list = false;
+        let mut separator = None;
+        let mut cdata = false;
+        let mut no_cdata = false;
+        let mut text_own = false;
+        let mut trim = false;
+        let mut normalize_space = false;
+        let mut mixed = false;
+        let mut other_elements = false;
+        let mut other_attributes = false;
+        let mut comment = false;
+        let mut map = None;
+        let mut item_name = None;
+        let mut repeated = false;
+        let mut flag = false;
+        let mut bool_format = None;
+        let mut float_format = None;
+        let mut precision = None;
+        let mut hex = false;
+        let mut plus_sign = false;
+        let mut from_str = false;
+        let mut display = false;
+        let mut deserialize_with = None;
+        let mut serialize_with = None;
         let mut namespace = None;
 
         for attr in attrs.iter().filter(|a| a.path.is_ident("easy_xml")) {
@@ -199,6 +229,14 @@ impl Attributes {
                                 }
                                 "text" => {
                                     text = true;
+                                    // text(own) 或 text(deep)
+                                    if let Some(mode) = get_mode(&mut attr_iter) {
+                                        match mode.as_str() {
+                                            "own" => text_own = true,
+                                            "deep" => text_own = false,
+                                            _ => panic!("text mode must be own or deep!"),
+                                        }
+                                    }
                                 }
                                 "flatten" => {
                                     flatten = true;
@@ -209,15 +247,101 @@ impl Attributes {
                                 "root" => {
                                     root = true;
                                 }
-                                "skip" => {
-                                    skip = true;
+                                // 与skip_serializing相同，反序列化时仍读取字段
+                                "skip" | "skip_serializing" => {
+                                    skip_serializing = true;
+                                }
+                                "skip_deserializing" => {
+                                    skip_deserializing = true;
+                                }
+                                "skip_serializing_if" => {
+                                    skip_serializing_if = get_value(&mut attr_iter);
                                 }
                                 "to_text" => {
                                     to_text = true;
                                 }
+                                "transparent" => {
+                                    transparent = true;
+                                }
                                 "container" => {
                                     container = true;
                                 }
+                                "list" => {
+                                    list = true;
+                                }
+                                "separator" => {
+                                    separator = get_value(&mut attr_iter);
+                                }
+                                "cdata" => {
+                                    cdata = true;
+                                }
+                                "no_cdata" => {
+                                    no_cdata = true;
+                                }
+                                "trim" => {
+                                    trim = true;
+                                }
+                                "normalize_space" => {
+                                    normalize_space = true;
+                                }
+                                "mixed" => {
+                                    mixed = true;
+                                }
+                                "other_elements" => {
+                                    other_elements = true;
+                                }
+                                "other_attributes" => {
+                                    other_attributes = true;
+                                }
+                                "comment" => {
+                                    comment = true;
+                                }
+                                "map" => {
+                                    map = Some(get_map_key(&mut attr_iter));
+                                }
+                                "repeated" => {
+                                    repeated = true;
+                                }
+                                "flag" => {
+                                    flag = true;
+                                }
+                                "bool_format" => {
+                                    bool_format = get_value(&mut attr_iter);
+                                }
+                                "float_format" => {
+                                    float_format = get_value(&mut attr_iter);
+                                }
+                                "precision" => {
+                                    precision = get_value(&mut attr_iter);
+                                }
+                                "hex" => {
+                                    hex = true;
+                                }
+                                "plus_sign" => {
+                                    plus_sign = true;
+                                }
+                                "from_str" => {
+                                    from_str = true;
+                                }
+                                "display" => {
+                                    display = true;
+                                }
+                                "with" => {
+                                    // 模块中的deserialize和serialize函数
+                                    if let Some(path) = get_value(&mut attr_iter) {
+                                        deserialize_with = Some(format!("{}::deserialize", path));
+                                        serialize_with = Some(format!("{}::serialize", path));
+                                    }
+                                }
+                                "deserialize_with" => {
+                                    deserialize_with = get_value(&mut attr_iter);
+                                }
+                                "serialize_with" => {
+                                    serialize_with = get_value(&mut attr_iter);
+                                }
+                                "item" => {
+                                    item_name = get_value(&mut attr_iter);
+                                }
                                 "prefix" => {
                                     prefix = get_value(&mut attr_iter);
                                 }
@@ -244,9 +368,36 @@ impl Attributes {
             rename,
             enums,
             root,
-            skip,
+            skip_serializing,
+            skip_deserializing,
+            skip_serializing_if,
             to_text,
             container,
+            transparent,
+            list,
+            separator,
+            cdata,
+            no_cdata,
+            text_own,
+            trim,
+            normalize_space,
+            mixed,
+            other_elements,
+            other_attributes,
+            comment,
+            map,
+            item: item_name,
+            repeated,
+            flag,
+            bool_format,
+            float_format,
+            precision,
+            hex,
+            plus_sign,
+            from_str,
+            display,
+            deserialize_with,
+            serialize_with,
             namespace: match namespace {
                 Some(map) => map,
                 None => BTreeMap::new(),
@@ -255,6 +406,43 @@ impl Attributes {
     }
 }
 
+// 读取map(key = "@name")或map(key = tag)
+fn get_map_key(iter: &mut IntoIter) -> MapKey {
+    if let Some(TokenTree::Group(group)) = iter.next() {
+        let mut inner = group.stream().into_iter();
+        if let (Some(TokenTree::Ident(key)), Some(TokenTree::Punct(operator)), Some(value)) =
+            (inner.next(), inner.next(), inner.next())
+        {
+            if key == "key" && operator.as_char() == '=' {
+                match value {
+                    TokenTree::Ident(ident) if ident == "tag" => return MapKey::Tag,
+                    TokenTree::Literal(literal) => {
+                        let literal = literal.to_string().replace("\"", "");
+                        if let Some(attribute) = literal.strip_prefix('@') {
+                            return MapKey::Attribute(attribute.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    panic!("map must be map(key = \"@attribute\") or map(key = tag)!")
+}
+
+// 读取紧跟在参数后面括号中的标识符，如text(own)
+fn get_mode(iter: &mut IntoIter) -> Option<String> {
+    if let Some(TokenTree::Group(group)) = iter.clone().next() {
+        iter.next();
+        if group.delimiter() == Delimiter::Parenthesis {
+            if let Some(TokenTree::Ident(ident)) = group.stream().into_iter().next() {
+                return Some(ident.to_string());
+            }
+        }
+    }
+    None
+}
+
 fn get_value(iter: &mut IntoIter) -> Option<String> {
     if let (Some(TokenTree::Punct(operator)), Some(TokenTree::Literal(value))) =
         (iter.next(), iter.next())
@@ -356,10 +544,10 @@ pub struct Field<'a> {
     index: i32,
 }
 impl<'a> Field<'a> {
-    pub fn from_struct(field: &'a syn::Field) -> Self {
+    pub fn from_struct(field: &'a syn::Field, index: i32) -> Self {
         Field {
             field,
-            index: -1,
+            index,
             attrs: Attributes::new(&field.attrs),
             ty: TypeWapper::new(&field.ty),
             is_struct: true,
@@ -385,14 +573,135 @@ impl<'a> Field<'a> {
     }
 
     pub fn check(&self) {
-        self.ty.type_check();
-
         let attrs = &self.attrs;
 
-        if self.ty.has_vec() {
-            if attrs.text {
-                panic!("Vec and text are mutually exclusive!")
+        if self.is_repeated() && attrs.text {
+            panic!("Vec and text are mutually exclusive!")
+        }
+
+        if (attrs.cdata || attrs.no_cdata) && !attrs.text {
+            panic!("cdata and no_cdata must be used with text!")
+        }
+
+        if (attrs.trim || attrs.normalize_space) && (attrs.flatten || attrs.container) {
+            panic!("trim and normalize_space can not be used with flatten or container!")
+        }
+
+        if attrs.list {
+            if attrs.flatten || attrs.container {
+                panic!("list can not be used with flatten or container!")
+            }
+        }
+
+        if attrs.mixed {
+            if !self.is_repeated() || self.is_optional() {
+                panic!("mixed must be used with a collection such as Vec<T>!")
+            }
+            if attrs.text || attrs.attribute || attrs.flatten || attrs.container || attrs.list {
+                panic!("mixed can not be used with text, attribute, flatten, container or list!")
+            }
+        }
+
+        if attrs.other_elements || attrs.other_attributes {
+            if !self.is_repeated() || self.is_optional() {
+                panic!(
+                    "other_elements and other_attributes must be used with a collection such as Vec<T>!"
+                )
             }
+            if attrs.text || attrs.attribute || attrs.flatten || attrs.container || attrs.mixed {
+                panic!(
+                    "other_elements and other_attributes can not be used with text, attribute, flatten, container or mixed!"
+                )
+            }
+        }
+
+        if attrs.map.is_some()
+            && (attrs.text
+                || attrs.attribute
+                || attrs.flatten
+                || attrs.list
+                || attrs.mixed
+                || attrs.comment
+                || attrs.container
+                || attrs.repeated
+                || self.ty.is_collection())
+        {
+            panic!(
+                "map can not be used with text, attribute, flatten, container, list, mixed, comment or Vec!"
+            )
+        }
+
+        if attrs.comment
+            && (attrs.text
+                || attrs.attribute
+                || attrs.flatten
+                || attrs.container
+                || attrs.list
+                || attrs.mixed)
+        {
+            panic!(
+                "comment can not be used with text, attribute, flatten, container, list or mixed!"
+            )
+        }
+
+        if attrs.item.is_some() && (!self.is_child() || attrs.list || attrs.map.is_some()) {
+            panic!("item must be used with child node fields!")
+        }
+
+        if attrs.repeated && attrs.list {
+            panic!("repeated and list are mutually exclusive!")
+        }
+
+        if (attrs.from_str || attrs.display)
+            && (attrs.flatten
+                || attrs.list
+                || attrs.mixed
+                || attrs.other_elements
+                || attrs.other_attributes
+                || attrs.comment
+                || attrs.map.is_some()
+                || attrs.item.is_some())
+        {
+            panic!(
+                "from_str and display can not be used with flatten, list, mixed, other_elements, other_attributes, comment, map or item!"
+            )
+        }
+
+        if (attrs.deserialize_with.is_some() || attrs.serialize_with.is_some())
+            && (attrs.from_str
+                || attrs.display
+                || attrs.list
+                || attrs.mixed
+                || attrs.other_elements
+                || attrs.other_attributes
+                || attrs.comment
+                || attrs.map.is_some()
+                || attrs.item.is_some())
+        {
+            panic!(
+                "with can not be used with from_str, display, list, mixed, other_elements, other_attributes, comment, map or item!"
+            )
+        }
+
+        if attrs.flag
+            && (!self.ty.is("bool")
+                || !(attrs.attribute || self.is_child())
+                || attrs.list
+                || attrs.container
+                || attrs.to_text
+                || attrs.map.is_some()
+                || attrs.item.is_some()
+                || attrs.from_str
+                || attrs.display
+                || attrs.deserialize_with.is_some()
+                || attrs.serialize_with.is_some()
+                || self.value_format().is_some())
+        {
+            panic!("flag must be used with a bool attribute or child node field!")
+        }
+
+        if self.is_positional() && (self.is_repeated() || attrs.map.is_some()) {
+            panic!("Unnamed collection or map field need rename!")
         }
 
         let mut count = 0;
@@ -411,6 +720,23 @@ impl<'a> Field<'a> {
         }
     }
 
+    // 没有rename的元组字段按子节点的顺序匹配，序列化时节点名称由字段类型决定
+    // 文本类型和from_str、display、with、list字段没有节点名称，必须rename
+    // transparent结构体的字段不经过这里
+    pub fn check_positional(&self) {
+        let attrs = &self.attrs;
+        if self.is_positional()
+            && (self.ty.is_scalar()
+                || attrs.list
+                || attrs.from_str
+                || attrs.display
+                || attrs.deserialize_with.is_some()
+                || attrs.serialize_with.is_some())
+        {
+            panic!("Unnamed scalar field need rename!")
+        }
+    }
+
     //临时变量名称
     pub fn var_name(&self) -> TokenStream {
         match self.field.ident.as_ref() {
@@ -419,20 +745,504 @@ impl<'a> Field<'a> {
         }
     }
 
-    //
-    pub fn field_name(&self) -> TokenStream {
-        match self.field.ident.as_ref() {
-            Some(i) => {
-                if self.is_struct {
-                    TokenStream::from_str(format!("self.{}", i.to_string()).as_str()).unwrap()
-                } else {
-                    TokenStream::from_str(format!("f_{}", i.to_string()).as_str()).unwrap()
-                }
-            }
-            None => TokenStream::from_str(format!("f_{}", self.index).as_str()).unwrap(),
+    //
+    pub fn field_name(&self) -> TokenStream {
+        match self.field.ident.as_ref() {
+            Some(i) => {
+                if self.is_struct {
+                    TokenStream::from_str(format!("self.{}", i.to_string()).as_str()).unwrap()
+                } else {
+                    TokenStream::from_str(format!("f_{}", i.to_string()).as_str()).unwrap()
+                }
+            }
+            None => {
+                if self.is_struct {
+                    TokenStream::from_str(format!("self.{}", self.index).as_str()).unwrap()
+                } else {
+                    TokenStream::from_str(format!("f_{}", self.index).as_str()).unwrap()
+                }
+            }
+        }
+    }
+
+    // 结构体字段名，元组字段为序号
+    pub fn member(&self) -> TokenStream {
+        match self.field.ident.as_ref() {
+            Some(i) => quote! {#i},
+            None => TokenStream::from_str(self.index.to_string().as_str()).unwrap(),
+        }
+    }
+
+    // 没有rename的元组字段按子节点的顺序匹配
+    pub fn is_positional(&self) -> bool {
+        self.field.ident.is_none() && self.attrs.rename.is_none() && self.is_child()
+    }
+
+    // 重复节点对应的集合类型：Vec<T>或Option<Vec<T>>中的Vec<T>
+    pub fn repeated_collection(&self) -> Option<TypeWapper> {
+        if self.attrs.list || self.attrs.map.is_some() {
+            return None;
+        }
+        if self.ty.is("Option") {
+            if let Some(inner) = self.ty.next_type() {
+                if self.attrs.repeated || inner.is_collection() {
+                    return Some(inner);
+                }
+            }
+            return None;
+        }
+        if self.attrs.repeated || self.ty.is_collection() {
+            return Some(TypeWapper::new(&self.ty.ty));
+        }
+        None
+    }
+
+    // 是否对应多个重复的节点
+    pub fn is_repeated(&self) -> bool {
+        self.repeated_collection().is_some()
+    }
+
+    // 最外层是否为Option
+    pub fn is_optional(&self) -> bool {
+        self.ty.is("Option")
+    }
+
+    // 重复节点中每一项的类型
+    fn repeated_item_type(&self) -> TokenStream {
+        let collection = (&self.repeated_collection().unwrap().ty).into_token_stream();
+        quote! {<#collection as easy_xml::XmlCollection>::Item}
+    }
+
+    // 对应一个节点或属性的值的类型，重复节点时为其中一项的类型
+    fn element_type(&self) -> TokenStream {
+        match self.is_repeated() {
+            true => self.repeated_item_type(),
+            false => (&self.ty.ty).into_token_stream(),
+        }
+    }
+
+    // 重复节点的所有项，Option<Vec<T>>为None时没有项
+    pub fn se_repeated_items(&self) -> TokenStream {
+        let field_name = self.field_name();
+        let collection = (&self.repeated_collection().unwrap().ty).into_token_stream();
+        if self.is_optional() {
+            quote! {#field_name.iter().flat_map(<#collection as easy_xml::XmlCollection>::items)}
+        } else {
+            quote! {<#collection as easy_xml::XmlCollection>::items(&#field_name)}
+        }
+    }
+
+    // from_str、display字段的值类型，Option<T>和Vec<T>中的T
+    fn scalar_type(&self) -> TokenStream {
+        if self.is_repeated() {
+            self.repeated_item_type()
+        } else if self.is_optional() {
+            (&self.ty.next_type().unwrap().ty).into_token_stream()
+        } else {
+            (&self.ty.ty).into_token_stream()
+        }
+    }
+
+    // 是否由字段参数指定了反序列化方式，此时字段类型不一定实现了XmlDeserialize
+    fn custom_de(&self) -> bool {
+        self.attrs.from_str || self.attrs.deserialize_with.is_some()
+    }
+
+    // transparent结构体：整个节点交给唯一的字段，集合类型按单个值处理
+    pub fn de_transparent_expr(&self) -> TokenStream {
+        if self.is_repeated() {
+            let ty = (&self.ty.ty).into_token_stream();
+            return quote! {
+              <#ty as easy_xml::XmlDeserialize>::deserialize(&element)?
+            };
+        }
+        self.de_item_expr()
+    }
+
+    // transparent结构体缺少对应节点时的值，与de_var_rebind一致
+    pub fn de_transparent_absent(&self) -> TokenStream {
+        let ty = (&self.ty.ty).into_token_stream();
+        if self.attrs.list || (self.custom_de() && self.is_optional()) {
+            quote! {Some(Default::default())}
+        } else if self.custom_de() {
+            quote! {None}
+        } else {
+            quote! {<#ty as easy_xml::XmlDeserialize>::absent()}
+        }
+    }
+
+    pub fn se_transparent_stmt(&self) -> TokenStream {
+        let field_name = self.field_name();
+        if self.is_repeated() {
+            return quote! {
+              #field_name.serialize(element);
+            };
+        }
+        self.se_item_stmt(field_name, quote! {element})
+    }
+
+    // 跳过序列化的字段不生成代码，skip_serializing_if为true时不写出
+    pub fn se_guard(&self, code: TokenStream) -> TokenStream {
+        if self.attrs.skip_serializing {
+            return quote! {};
+        }
+        match &self.attrs.skip_serializing_if {
+            Some(path) => {
+                let path = TokenStream::from_str(path).unwrap();
+                let field_name = self.field_name();
+                quote! {
+                  if !#path(&#field_name) {
+                    #code
+                  }
+                }
+            }
+            None => code,
+        }
+    }
+
+    // 是否写出对应的属性或子节点
+    pub fn se_present(&self) -> TokenStream {
+        let field_name = self.field_name();
+        if self.attrs.flag {
+            // 为true时才写出
+            return quote! {bool::clone(&#field_name)};
+        }
+        let custom_se =
+            self.attrs.list || self.attrs.display || self.attrs.serialize_with.is_some();
+        match (custom_se, self.is_optional()) {
+            (true, true) => quote! {#field_name.is_some()},
+            (true, false) => quote! {true},
+            (false, _) => quote! {!#field_name.is_absent()},
+        }
+    }
+
+    // list字段的集合类型，Option<Vec<T>>中的Vec<T>
+    fn list_collection(&self) -> TokenStream {
+        match self.is_optional() {
+            true => (&self.ty.next_type().unwrap().ty).into_token_stream(),
+            false => (&self.ty.ty).into_token_stream(),
+        }
+    }
+
+    // 从element中反序列化出一项的表达式
+    pub fn de_item_expr(&self) -> TokenStream {
+        let item = self.de_plain_item_expr();
+        match self.value_format() {
+            Some(format) => quote! {
+              easy_xml::format::with_format(#format, || -> Result<_, easy_xml::de::Error> {
+                let item = #item;
+                Ok(item)
+              })?
+            },
+            None => item,
+        }
+    }
+
+    // 字段上指定的bool、数字格式，未指定的部分沿用当前格式
+    fn value_format(&self) -> Option<TokenStream> {
+        let attrs = &self.attrs;
+        let mut code = TokenStream::new();
+        if let Some(bool_format) = &attrs.bool_format {
+            let variant = match bool_format.as_str() {
+                "strict" => quote! {Strict},
+                "xsd" => quote! {Xsd},
+                "numeric" => quote! {Numeric},
+                "yes_no" => quote! {YesNo},
+                "yn" => quote! {Yn},
+                other => panic!("Unknown bool_format {}!", other),
+            };
+            code.extend(quote! {
+              format.bool_format = easy_xml::format::BoolFormat::#variant;
+            });
+        }
+        if let Some(float_format) = &attrs.float_format {
+            let variant = match float_format.as_str() {
+                "display" => quote! {Display},
+                "xsd" => quote! {Xsd},
+                other => panic!("Unknown float_format {}!", other),
+            };
+            code.extend(quote! {
+              format.float_format = easy_xml::format::FloatFormat::#variant;
+            });
+        }
+        if let Some(precision) = &attrs.precision {
+            let precision: usize = match precision.parse() {
+                Ok(precision) => precision,
+                Err(_) => panic!("precision must be an integer!"),
+            };
+            code.extend(quote! {
+              format.precision = Some(#precision);
+            });
+        }
+        if attrs.hex {
+            code.extend(quote! {
+              format.hex = true;
+            });
+        }
+        if attrs.plus_sign {
+            code.extend(quote! {
+              format.plus_sign = true;
+            });
+        }
+        if code.is_empty() {
+            return None;
+        }
+        Some(quote! {
+          {
+            let mut format = easy_xml::format::current();
+            #code
+            format
+          }
+        })
+    }
+
+    fn de_plain_item_expr(&self) -> TokenStream {
+        if self.attrs.list {
+            let collection = self.list_collection();
+            let separator = self.list_separator();
+            let list = quote! {
+              easy_xml::de::list_from_element::<#collection>(&element, #separator)?
+            };
+            return match self.is_optional() {
+                true => quote! {Some(#list)},
+                false => list,
+            };
+        }
+        if self.custom_de() {
+            let parse = match &self.attrs.deserialize_with {
+                Some(path) => {
+                    let path = TokenStream::from_str(path).unwrap();
+                    quote! {#path(&element)?}
+                }
+                None => {
+                    let scalar_ty = self.scalar_type();
+                    quote! {easy_xml::de::parse_from_element::<#scalar_ty>(&element)?}
+                }
+            };
+            // Option<T>字段按T处理
+            return match !self.is_repeated() && self.is_optional() {
+                true => quote! {Some(#parse)},
+                false => parse,
+            };
+        }
+        let item_ty = self.element_type();
+        if let Some(name) = &self.attrs.item {
+            return quote! {
+              easy_xml::de::items_from_element::<#item_ty>(&element, Some(#name))?
+            };
+        }
+        quote! {
+          <#item_ty as easy_xml::XmlDeserialize>::deserialize(&element)?
+        }
+    }
+
+    // 将value序列化到target中的语句
+    pub fn se_item_stmt(&self, value: TokenStream, target: TokenStream) -> TokenStream {
+        let stmt = self.se_plain_item_stmt(value, target);
+        match self.value_format() {
+            Some(format) => quote! {
+              easy_xml::format::with_format(#format, || {
+                #stmt
+              });
+            },
+            None => stmt,
+        }
+    }
+
+    fn se_plain_item_stmt(&self, value: TokenStream, target: TokenStream) -> TokenStream {
+        if self.attrs.flag {
+            // 只写出空节点，属性值由调用方决定
+            return quote! {};
+        }
+        if self.attrs.list {
+            let collection = self.list_collection();
+            let separator = match &self.attrs.separator {
+                Some(separator) => separator.clone(),
+                None => " ".to_string(),
+            };
+            if self.is_optional() {
+                return quote! {
+                  if let Some(value) = (#value).as_ref() {
+                    easy_xml::se::list_to_element::<#collection>(value, #separator, #target);
+                  }
+                };
+            }
+            return quote! {
+              easy_xml::se::list_to_element::<#collection>(&#value, #separator, #target);
+            };
+        }
+        if self.attrs.display || self.attrs.serialize_with.is_some() {
+            let path = match &self.attrs.serialize_with {
+                Some(path) => TokenStream::from_str(path).unwrap(),
+                None => quote! {easy_xml::se::display_to_element},
+            };
+            // Option<T>字段按T处理
+            if !self.is_repeated() && self.is_optional() {
+                return quote! {
+                  if let Some(value) = (#value).as_ref() {
+                    #path(value, #target);
+                  }
+                };
+            }
+            return quote! {
+              #path(&#value, #target);
+            };
+        }
+        if let Some(name) = &self.attrs.item {
+            let item_ty = self.element_type();
+            return quote! {
+              easy_xml::se::items_to_element::<#item_ty>(&#value, Some(#name), #target);
+            };
+        }
+        quote! {
+          #value.serialize(#target);
+        }
+    }
+
+    // map字段：从节点中取出键和值插入map
+    fn de_map_insert(&self, key: &MapKey) -> TokenStream {
+        let var_name = self.var_name();
+        let insert = quote! {
+          #var_name.insert(
+            easy_xml::XmlDeserialize::deserialize(&easy_xml::XmlElement::Text(key))?,
+            easy_xml::XmlDeserialize::deserialize(element)?,
+          );
+        };
+        match key {
+            MapKey::Attribute(attribute) => {
+                let insert = quote! {
+                  let key = match node.attributes.iter().find(|attr| attr.name.local_name == #attribute) {
+                    Some(attr) => attr.value.clone(),
+                    None => {
+                      return Err(easy_xml::de::Error::Other(
+                        format!("Missing map key attribute {}!", #attribute),
+                      ))
+                    }
+                  };
+                  #insert
+                };
+                quote! {{#insert}}
+            }
+            MapKey::Tag => quote! {
+              for element in &node.elements {
+                if let easy_xml::XmlElement::Node(node) = element {
+                  let key = node.borrow().name.local_name.clone();
+                  #insert
+                }
+              }
+            },
+        }
+    }
+
+    // map字段：每一项写为一个子节点
+    fn se_map(&self, key: &MapKey, local_name: TokenStream, prefix: TokenStream) -> TokenStream {
+        let field_name = self.field_name();
+        let key_text = quote! {
+          let key = {
+            let mut text = easy_xml::XmlElement::Text(String::new());
+            key.serialize(&mut text);
+            match text {
+              easy_xml::XmlElement::Text(text) => text,
+              _ => String::new(),
+            }
+          };
+        };
+        let item = match key {
+            MapKey::Attribute(attribute) => quote! {
+              let mut child = easy_xml::XmlNode::empty();
+              child.name.local_name = #local_name;
+              child.name.prefix = #prefix;
+              #key_text
+              child.attributes.push(easy_xml::OwnedAttribute {
+                name: easy_xml::OwnedName::local(#attribute),
+                value: key,
+              });
+            },
+            MapKey::Tag => quote! {
+              let mut child = easy_xml::XmlNode::empty();
+              #key_text
+              child.name.local_name = key;
+            },
+        };
+        let item = quote! {
+          #item
+          let mut child = easy_xml::XmlElement::Node(child.into_ref());
+          value.serialize(&mut child);
+        };
+        if key == &MapKey::Tag {
+            quote! {
+              {
+                let mut container = easy_xml::XmlNode::empty();
+                container.name.local_name = #local_name;
+                container.name.prefix = #prefix;
+                for (key, value) in #field_name.iter() {
+                  #item
+                  container.elements.push(child);
+                }
+                node.borrow_mut().elements.push(easy_xml::XmlElement::Node(container.into_ref()));
+              }
+            }
+        } else {
+            quote! {
+              for (key, value) in #field_name.iter() {
+                #item
+                node.borrow_mut().elements.push(child);
+              }
+            }
+        }
+    }
+
+    // 提取文本时的选项
+    pub fn de_text_options(&self) -> TokenStream {
+        let cdata = !self.attrs.no_cdata;
+        let deep = !self.attrs.text_own;
+        let trim = self.attrs.trim;
+        let normalize_space = self.attrs.normalize_space;
+        quote! {
+          easy_xml::TextOptions {
+            cdata: #cdata,
+            deep: #deep,
+            trim: #trim,
+            normalize_space: #normalize_space,
+          }
+        }
+    }
+
+    // 属性值或子节点需要先处理空白时，用处理后的文本替换element
+    pub fn de_text_rebind(&self) -> TokenStream {
+        if !(self.attrs.trim || self.attrs.normalize_space) {
+            return quote! {};
+        }
+        let options = self.de_text_options();
+        quote! {
+          let element = {
+            let mut text = String::new();
+            element.text_with(&mut text, &#options);
+            easy_xml::XmlElement::Text(text)
+          };
+          let element = &element;
+        }
+    }
+
+    fn list_separator(&self) -> TokenStream {
+        match &self.attrs.separator {
+            Some(separator) => quote! {Some(#separator)},
+            None => quote! {None},
         }
     }
 
+    // 是否为普通的子节点字段
+    pub fn is_child(&self) -> bool {
+        let attrs = &self.attrs;
+        !(attrs.attribute
+            || attrs.text
+            || attrs.flatten
+            || attrs.mixed
+            || attrs.other_elements
+            || attrs.other_attributes
+            || attrs.comment)
+    }
+
     pub fn multi_tag(&self) -> bool {
         if let Some(rename) = &self.attrs.rename {
             return rename.contains("|");
@@ -480,86 +1290,144 @@ impl<'a> Field<'a> {
     pub fn de_var_declare(&self) -> TokenStream {
         let var_name = self.var_name();
 
-        let ty = &self.ty;
-        let token = {
-            if ty.has_vec() {
-                ((&ty.ty).into_token_stream(), quote! {Vec::new()})
-            } else if ty.has_option() {
-                let type_token = (&ty.ty).into_token_stream();
-                (quote! {Box<#type_token>}, quote! {Box::new(None)})
+        let type_token = (&self.ty.ty).into_token_stream();
+        let (var_type, var_value) = {
+            if self.is_repeated() {
+                // 先收集到Vec中，最后再转换为集合
+                let item_ty = self.repeated_item_type();
+                match self.is_optional() {
+                    // Option<Vec<T>>，有匹配的节点时才为Some
+                    true => (quote! {Option<Vec<#item_ty>>}, quote! {None}),
+                    false => (quote! {Vec<#item_ty>}, quote! {Vec::new()}),
+                }
+            } else if self.attrs.map.is_some() {
+                // 缺少时为空map
+                (type_token, quote! {Default::default()})
             } else {
-                let type_token = (&ty.ty).into_token_stream();
                 (quote! {Box<Option<#type_token>>}, quote! {Box::new(None)})
             }
         };
 
-        let var_type = token.0;
-        let var_value = token.1;
-
         quote! {
           let mut #var_name:#var_type =  #var_value ;
         }
     }
 
-    pub fn de_get_var_instance(&self) -> TokenStream {
-        let ty = TypeWapper::new(&self.field.ty);
-
-        let is_vec = ty.has_vec();
-
-        let ty = {
-            if is_vec {
-                ty.next_type().unwrap()
-            } else {
-                ty
+    // container中的项为空时Option<Vec<T>>也为Some
+    pub fn de_container_present(&self) -> TokenStream {
+        if self.is_repeated() && self.is_optional() {
+            let var_name = self.var_name();
+            quote! {
+              #var_name.get_or_insert_with(Vec::new);
             }
-        };
-        let token = TokenStream::from_str(ty.full_path().as_str()).unwrap();
+        } else {
+            quote! {}
+        }
+    }
 
+    pub fn de_get_var_instance(&self) -> TokenStream {
         let var_name = self.var_name();
-
-        if is_vec {
-            return quote! {
-              // let field___val : #ty_token =  ;
-              #var_name.push(#token::deserialize(&element)?);
-            };
-        } else if ty.has_option() {
+        if self.attrs.flag {
+            // 属性或节点存在即为true
             return quote! {
-              *#var_name = #token::deserialize(&element)?;
+              *#var_name = Some(true);
             };
+        }
+        let item = self.de_item_expr();
+
+        if !self.is_repeated() {
+            quote! {
+              *#var_name = Some(#item);
+            }
+        } else if self.is_optional() {
+            quote! {
+              #var_name.get_or_insert_with(Vec::new).push(#item);
+            }
         } else {
             quote! {
-              *#var_name = Some(#token::deserialize(&element)?);
+              #var_name.push(#item);
             }
         }
     }
 }
 
+// 标记为text的变体，只能有一个且必须是只有一个字段的元组变体
+pub fn text_variant(data: &syn::DataEnum) -> Option<&syn::Variant> {
+    let mut variants = data
+        .variants
+        .iter()
+        .filter(|v| Attributes::new(&v.attrs).text);
+    let variant = variants.next();
+    if variants.next().is_some() {
+        panic!("Only one variant can be text!")
+    }
+    if let Some(v) = variant {
+        match &v.fields {
+            syn::Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {}
+            _ => panic!("text variant must have exactly one unnamed field!"),
+        }
+    }
+    variant
+}
+
+// transparent结构体只能有一个字段，且字段不能指定节点位置相关的参数
+pub fn transparent_field<'a, 'b>(fields: &'b [Field<'a>]) -> &'b Field<'a> {
+    if fields.len() != 1 {
+        panic!("transparent struct must have exactly one field!")
+    }
+    let f = &fields[0];
+    let attrs = &f.attrs;
+    if !f.is_child()
+        || attrs.rename.is_some()
+        || attrs.prefix.is_some()
+        || attrs.container
+        || attrs.to_text
+        || attrs.map.is_some()
+        || attrs.item.is_some()
+        || attrs.skip_serializing
+        || attrs.skip_deserializing
+        || attrs.skip_serializing_if.is_some()
+    {
+        panic!("transparent field can only use list, from_str, display or with!")
+    }
+    if f.is_repeated() && (f.custom_de() || attrs.display || attrs.serialize_with.is_some()) {
+        panic!("transparent collection field can not use from_str, display or with!")
+    }
+    f
+}
+
 pub fn de_build_code_for_declare(fields: &Vec<Field>) -> TokenStream {
-    fields.into_iter().map(|f| f.de_var_declare()).collect()
+    fields
+        .into_iter()
+        .filter(|f| !f.attrs.skip_deserializing)
+        .map(|f| f.de_var_declare())
+        .collect()
 }
 
 pub fn de_build_code_for_text(fields: &Vec<Field>) -> TokenStream {
     let text_code: TokenStream = (&fields)
         .into_iter()
-        .filter(|f| f.attrs.text)
-        .map(|f| f.de_get_var_instance())
+        .filter(|f| f.attrs.text && !f.attrs.skip_deserializing)
+        .map(|f| {
+            let options = f.de_text_options();
+            let var_instance = f.de_get_var_instance();
+            quote! {
+              {
+                let mut text = String::new();
+                element.text_with(&mut text, &#options);
+                let element = easy_xml::XmlElement::Text(text);
+                #var_instance
+              }
+            }
+        })
         .collect();
-
-    let text_code = quote! {
-        {
-          let mut text = String::new();
-          element.text(&mut text);
-          let element = easy_xml::XmlElement::Text(text);
-          #text_code
-        }
-    };
     return text_code;
 }
 
 pub fn de_build_code_for_flatten(fields: &Vec<Field>) -> TokenStream {
     let flatten_code: TokenStream = (&fields)
         .into_iter()
-        .filter(|f| f.attrs.flatten)
+        .filter(|f| f.attrs.flatten && !f.attrs.skip_deserializing)
         .map(|f| f.de_get_var_instance())
         .collect();
 
@@ -572,28 +1440,48 @@ pub fn de_build_code_for_flatten(fields: &Vec<Field>) -> TokenStream {
 }
 
 pub fn de_build_code_for_attribute(fields: &Vec<Field>) -> TokenStream {
+    let (mark, collect) = de_other(fields, |f| f.attrs.other_attributes, quote! {attr});
+    let matched = match collect.is_empty() {
+        true => quote! {},
+        false => quote! {
+          let mut matched = false;
+        },
+    };
     let mut count = 0;
     let attribute_code: TokenStream = (&fields)
         .into_iter()
-        .filter(|f| f.attrs.attribute)
+        .filter(|f| f.attrs.attribute && !f.attrs.skip_deserializing)
         .map(|f| {
             count += 1;
             let owned_name_match = f.de_owned_name_match();
             let var_instance = f.de_get_var_instance();
+            if f.attrs.flag {
+                return quote! {
+                  if #owned_name_match {
+                    #mark
+                    #var_instance
+                  }
+                };
+            }
+            let text_rebind = f.de_text_rebind();
             quote! {
               if #owned_name_match {
+                #mark
                 let element = easy_xml::XmlElement::Text(attr.value.clone());
+                #text_rebind
                 #var_instance
               }
             }
         })
         .collect();
 
-    if count > 0 {
+    if count > 0 || !collect.is_empty() {
         quote! {
           for attr in &node.attributes {
             let name = &attr.name;
+            #matched
             #attribute_code
+            #collect
           }
         }
     } else {
@@ -601,17 +1489,62 @@ pub fn de_build_code_for_attribute(fields: &Vec<Field>) -> TokenStream {
     }
 }
 
+// 未匹配的属性或子节点收集到other字段中，返回标记匹配和收集的代码
+fn de_other(
+    fields: &[Field],
+    predicate: fn(&Field) -> bool,
+    item: TokenStream,
+) -> (TokenStream, TokenStream) {
+    match fields
+        .iter()
+        .find(|f| predicate(f) && !f.attrs.skip_deserializing)
+    {
+        Some(f) => {
+            let var_name = f.var_name();
+            (
+                quote! {
+                  matched = true;
+                },
+                quote! {
+                  if !matched {
+                    #var_name.push(#item.clone());
+                  }
+                },
+            )
+        }
+        None => (quote! {}, quote! {}),
+    }
+}
+
 pub fn de_build_code_for_children(fields: &Vec<Field>) -> TokenStream {
+    let (mark, collect) = de_other(fields, |f| f.attrs.other_elements, quote! {element});
+    if !collect.is_empty() && fields.iter().any(|f| f.attrs.flatten) {
+        // flatten字段匹配的节点无法得知，会被重复收集
+        panic!("other_elements can not be used with flatten fields!")
+    }
     let mut count = 0;
+    let mut positional = 0usize;
     let code: TokenStream = (&fields)
         .into_iter()
-        .filter(|f| f.attrs.attribute == false && f.attrs.text == false && f.attrs.flatten == false)
+        .filter(|f| f.is_child() && !f.attrs.skip_deserializing)
         .map(|f| {
             count += 1;
-            let owned_name_match = f.de_owned_name_match();
+            let owned_name_match = match f.is_positional() {
+                true => {
+                    // 第n个未重命名的元组字段对应第n个子节点
+                    let index = positional;
+                    positional += 1;
+                    quote! {position == #index}
+                }
+                false => f.de_owned_name_match(),
+            };
             let var_instance = f.de_get_var_instance();
             let var_instance = {
-                if f.attrs.to_text {
+                if let Some(key) = &f.attrs.map {
+                    f.de_map_insert(key)
+                } else if f.attrs.flag {
+                    var_instance
+                } else if f.attrs.to_text {
                     quote! {
                       let mut text = String::new();
                       node.text(&mut text);
@@ -619,32 +1552,62 @@ pub fn de_build_code_for_children(fields: &Vec<Field>) -> TokenStream {
                       #var_instance
                     }
                 } else if f.attrs.container {
+                    let present = f.de_container_present();
                     quote! {
+                      #present
                       for element in &node.elements {
                         #var_instance
                       }
                     }
                 } else {
-                    quote! {{#var_instance}}
+                    let text_rebind = f.de_text_rebind();
+                    quote! {{
+                      #text_rebind
+                      #var_instance
+                    }}
                 }
             };
 
             quote! {
               if #owned_name_match {
+                #mark
                 #var_instance
               }
             }
         })
         .collect();
 
-    if count > 0 {
+    let matched = match collect.is_empty() {
+        true => quote! {},
+        false => quote! {
+          let mut matched = false;
+        },
+    };
+
+    let (position, next_position) = match positional > 0 {
+        true => (
+            quote! {
+              let mut position = 0usize;
+            },
+            quote! {
+              position += 1;
+            },
+        ),
+        false => (quote! {}, quote! {}),
+    };
+
+    if count > 0 || !collect.is_empty() {
         quote! {
+          #position
           for element in &node.elements {
             match element {
               easy_xml::XmlElement::Node(node) => {
                   let node = &*node.borrow();
                   let name = &node.name;
+                  #matched
                   #code
+                  #collect
+                  #next_position
               }
               _ => {}
             }
@@ -655,21 +1618,132 @@ pub fn de_build_code_for_children(fields: &Vec<Field>) -> TokenStream {
     }
 }
 
+// 注释：Vec中每个注释一项，否则将所有注释拼接
+pub fn de_build_code_for_comment(fields: &Vec<Field>) -> TokenStream {
+    fields
+        .iter()
+        .filter(|f| f.attrs.comment && !f.attrs.skip_deserializing)
+        .map(|f| {
+            let var_instance = f.de_get_var_instance();
+            if f.is_repeated() {
+                quote! {
+                  for element in &node.elements {
+                    if let easy_xml::XmlElement::Comment(comment) = element {
+                      let element = easy_xml::XmlElement::Text(comment.clone());
+                      #var_instance
+                    }
+                  }
+                }
+            } else {
+                quote! {
+                  {
+                    let mut comments: Option<String> = None;
+                    for element in &node.elements {
+                      if let easy_xml::XmlElement::Comment(comment) = element {
+                        comments.get_or_insert_with(String::new).push_str(comment);
+                      }
+                    }
+                    if let Some(comments) = comments {
+                      let element = easy_xml::XmlElement::Text(comments);
+                      #var_instance
+                    }
+                  }
+                }
+            }
+        })
+        .collect()
+}
+
+// 混合内容：按文档顺序收集文本和子节点
+pub fn de_build_code_for_mixed(fields: &Vec<Field>) -> TokenStream {
+    fields
+        .iter()
+        .filter(|f| f.attrs.mixed && !f.attrs.skip_deserializing)
+        .map(|f| {
+            let var_name = f.var_name();
+            let item = f.repeated_item_type();
+            quote! {
+              {
+                // 连续的文本和CDATA合并后读取
+                let mut text = String::new();
+                for element in node.elements.iter().map(Some).chain(std::iter::once(None)) {
+                  match element {
+                    Some(easy_xml::XmlElement::Text(value))
+                    | Some(easy_xml::XmlElement::Whitespace(value))
+                    | Some(easy_xml::XmlElement::CData(value)) => {
+                      text.push_str(value);
+                      continue;
+                    }
+                    Some(easy_xml::XmlElement::Comment(_)) => continue,
+                    _ => {}
+                  }
+                  if !text.is_empty() {
+                    if let Some(item) = <#item as easy_xml::XmlDeserialize>::deserialize_mixed_text(&text) {
+                      #var_name.push(item?);
+                    }
+                    text.clear();
+                  }
+                  if let Some(element @ easy_xml::XmlElement::Node(child)) = element {
+                    // 未匹配的节点忽略，匹配的节点读取失败时返回错误
+                    if <#item as easy_xml::XmlDeserialize>::matches_mixed_node(&child.borrow()) {
+                      #var_name.push(<#item as easy_xml::XmlDeserialize>::deserialize(element)?);
+                    }
+                  }
+                }
+              }
+            }
+        })
+        .collect()
+}
+
 pub fn de_var_rebind(fields: &Vec<Field>) -> TokenStream {
     (&fields)
         .into_iter()
         .map(|f| {
             let var_name = f.var_name();
-            if f.ty.has_vec() {
+            if f.attrs.skip_deserializing {
+                // 跳过反序列化的字段取默认值
+                let ty = (&f.ty.ty).into_token_stream();
+                quote! {
+                  let #var_name: #ty = Default::default();
+                }
+            } else if let Some(collection) = f.repeated_collection() {
+                let collection = (&collection.ty).into_token_stream();
+                if f.is_optional() {
+                    quote! {
+                      let #var_name = match #var_name {
+                        Some(items) => Some(<#collection as easy_xml::XmlCollection>::from_items(items)?),
+                        None => None,
+                      };
+                    }
+                } else {
+                    quote! {
+                      let #var_name = <#collection as easy_xml::XmlCollection>::from_items(#var_name)?;
+                    }
+                }
+            } else if f.attrs.map.is_some() {
                 quote! {}
-            } else if f.ty.has_option() {
+            } else if f.attrs.list
+                || f.attrs.flag
+                || (f.attrs.comment && !f.is_optional())
+                || (f.custom_de() && f.is_optional())
+            {
+                // 缺少时为空集合、空字符串、false或None
                 quote! {
-                  let #var_name = *#var_name;
+                  let #var_name = (*#var_name).unwrap_or_default();
                 }
-            } else {
+            } else if f.custom_de() {
                 quote! {
                   let #var_name = easy_xml::de::unwrap_option(*#var_name)?;
                 }
+            } else {
+                // 缺少时由类型决定，如Option<T>为None，其余类型报错
+                let ty = (&f.ty.ty).into_token_stream();
+                quote! {
+                  let #var_name = easy_xml::de::unwrap_option(
+                    (*#var_name).or_else(<#ty as easy_xml::XmlDeserialize>::absent),
+                  )?;
+                }
             }
         })
         .collect()
@@ -738,16 +1812,32 @@ pub fn se_build_code_for_text(fields: &Vec<Field>) -> TokenStream {
     let mut count = 0;
     let code_text: TokenStream = fields
         .into_iter()
-        .filter(|f| f.attrs.text)
+        .filter(|f| f.attrs.text && !f.attrs.cdata && !f.attrs.skip_serializing)
         .map(|f| {
             count += 1;
             let field_name = f.field_name();
-            quote! {
-              #field_name.serialize(&mut text);
-            }
+            f.se_guard(f.se_item_stmt(field_name, quote! {&mut text}))
         })
         .collect();
-    if count > 0 {
+    // 写成CDATA的字段各自单独一个CDATA
+    let code_cdata: TokenStream = fields
+        .into_iter()
+        .filter(|f| f.attrs.text && f.attrs.cdata)
+        .map(|f| {
+            let field_name = f.field_name();
+            let serialize = f.se_item_stmt(field_name, quote! {&mut text});
+            f.se_guard(quote! {
+              {
+                let mut text = easy_xml::XmlElement::Text(String::new());
+                #serialize
+                if let easy_xml::XmlElement::Text(text) = text {
+                  node.borrow_mut().elements.push(easy_xml::XmlElement::CData(text));
+                }
+              }
+            })
+        })
+        .collect();
+    let code_text = if count > 0 {
         quote! {
           {
             let mut text = easy_xml::XmlElement::Text(String::new());
@@ -757,6 +1847,10 @@ pub fn se_build_code_for_text(fields: &Vec<Field>) -> TokenStream {
         }
     } else {
         quote! {}
+    };
+    quote! {
+      #code_text
+      #code_cdata
     }
 }
 
@@ -764,12 +1858,7 @@ pub fn se_build_code_for_flatten(fields: &Vec<Field>) -> TokenStream {
     let code: TokenStream = fields
         .into_iter()
         .filter(|f| f.attrs.flatten == true)
-        .map(|f| {
-            let field_name = f.field_name();
-            quote! {
-              #field_name.serialize(element);
-            }
-        })
+        .map(|f| f.se_guard(f.se_item_stmt(f.field_name(), quote! {element})))
         .collect();
     quote! {
       {
@@ -782,7 +1871,7 @@ pub fn se_build_code_for_attribute(fields: &Vec<Field>) -> TokenStream {
     let mut count = 0;
     let code: TokenStream = fields
         .into_iter()
-        .filter(|f| f.attrs.attribute)
+        .filter(|f| f.attrs.attribute && !f.attrs.skip_serializing)
         .map(|f| {
             count += 1;
             let field_name = f.field_name();
@@ -806,10 +1895,19 @@ pub fn se_build_code_for_attribute(fields: &Vec<Field>) -> TokenStream {
                 },
                 None => quote! {None},
             };
-            quote! {
-              {
+            let present = f.se_present();
+            let serialize = match f.attrs.flag {
+                // flag属性写为disabled="disabled"的形式
+                true => quote! {
+                  text = easy_xml::XmlElement::Text(#local_name);
+                },
+                false => f.se_item_stmt(field_name, quote! {&mut text}),
+            };
+            f.se_guard(quote! {
+              // 缺少的属性不写出
+              if #present {
                 let mut text = easy_xml::XmlElement::Text(String::new());
-                #field_name.serialize(&mut text);
+                #serialize
                 //这里可以使用text()但是会多一次String复制
                 match text {
                     easy_xml::XmlElement::Text(value) => {
@@ -824,7 +1922,18 @@ pub fn se_build_code_for_attribute(fields: &Vec<Field>) -> TokenStream {
                     _ => {}
                 }
             }
-            }
+            })
+        })
+        .collect();
+    // 反序列化时未匹配的属性追加在最后
+    let code_other: TokenStream = fields
+        .iter()
+        .filter(|f| f.attrs.other_attributes)
+        .map(|f| {
+            let items = f.se_repeated_items();
+            f.se_guard(quote! {
+              node.borrow_mut().attributes.extend(#items.cloned());
+            })
         })
         .collect();
     if count > 0 {
@@ -832,124 +1941,189 @@ pub fn se_build_code_for_attribute(fields: &Vec<Field>) -> TokenStream {
           {
             #code
           }
+          #code_other
         }
     } else {
-        quote! {}
+        code_other
     }
 }
 
-pub fn se_build_code_for_node(fields: &Vec<Field>) -> TokenStream {
-    let code: TokenStream = fields
-        .into_iter()
-        .filter(|f| {
-            f.attrs.text == false
-                && f.attrs.attribute == false
-                && f.attrs.flatten == false
-                && f.attrs.skip == false
-        })
+// 注释写在节点内容的最前面
+pub fn se_build_code_for_comment(fields: &Vec<Field>) -> TokenStream {
+    fields
+        .iter()
+        .filter(|f| f.attrs.comment)
         .map(|f| {
             let field_name = f.field_name();
-
-            let local_name = match &f.attrs.rename {
-                // 节点名称由子节点自己决定
-                Some(rename) => match f.multi_tag() {
-                    true => quote! {
-                      "".to_string()
-                    },
-                    false => quote! {
-                      #rename.to_string()
-                    },
-                },
-                None => match f.field.ident.as_ref() {
-                    Some(ident) => {
-                        let ident = ident.to_string();
-                        quote! {
-                          #ident.to_string()
-                        }
-                    }
-                    None => {
-                        panic!("Unnamed enum need rename!")
-                    }
-                },
+            let items = if f.is_repeated() {
+                f.se_repeated_items()
+            } else if f.is_optional() {
+                quote! {#field_name.as_ref().into_iter()}
+            } else {
+                quote! {std::iter::once(&#field_name)}
             };
-            let prefix = match &f.attrs.prefix {
-                Some(prefix) => quote! {
-                  Some(#prefix.to_string())
+            // 单个字符串缺少注释时读取为空，为空时不写出注释
+            let skip_empty = match !f.is_repeated() && !f.is_optional() {
+                true => quote! {
+                  if text.is_empty() {
+                    continue;
+                  }
                 },
-                None => quote! {None},
+                false => quote! {},
             };
-            if f.ty.has_vec() {
-                if f.attrs.container {
-                    quote! {
-                      {
-                        if #field_name.len() > 0 {
-                          let mut container = easy_xml::XmlNode::empty();
-                          container.name.local_name = #local_name;
-                          container.name.prefix = #prefix;
-                          for item in &#field_name{
-                            let mut child = easy_xml::XmlNode::empty();
-                            let child = std::rc::Rc::new(std::cell::RefCell::new(child));
-                            let mut child = easy_xml::XmlElement::Node(child);
-                            item.serialize(&mut child);
-                            container.elements.push(child);
-                          }
-                          let container = std::rc::Rc::new(std::cell::RefCell::new(container));
-                          let mut container = easy_xml::XmlElement::Node(container);
-                          node.borrow_mut().elements.push(container);
-                        }
-                      }
-                    }
-                } else {
-                    quote! {
-                      {
-                        for item in &#field_name{
-                          let mut child = easy_xml::XmlNode::empty();
-                          child.name.local_name = #local_name;
-                          child.name.prefix = #prefix;
-
-                          let child = std::rc::Rc::new(std::cell::RefCell::new(child));
-                          let mut child = easy_xml::XmlElement::Node(child);
-                          item.serialize(&mut child);
-                          node.borrow_mut().elements.push(child);
-                        }
-                      }
-                    }
+            f.se_guard(quote! {
+              for item in #items {
+                let mut text = easy_xml::XmlElement::Text(String::new());
+                item.serialize(&mut text);
+                if let easy_xml::XmlElement::Text(text) = text {
+                  #skip_empty
+                  node.borrow_mut().elements.push(easy_xml::XmlElement::Comment(text));
                 }
-            } else {
-                if f.ty.has_option() {
-                    quote! {
-                      {
-                        if let Some(item) = &#field_name {
-                          let mut child = easy_xml::XmlNode::empty();
-                          child.name.local_name = #local_name;
-                          child.name.prefix = #prefix;
-
-                          let child = std::rc::Rc::new(std::cell::RefCell::new(child));
-                          let mut child = easy_xml::XmlElement::Node(child);
-                          item.serialize(&mut child);
-                          node.borrow_mut().elements.push(child);
-                        }
-                      }
-                    }
-                } else {
-                    quote! {
-                      {
-                        let mut child = easy_xml::XmlNode::empty();
-                        child.name.local_name = #local_name;
-                        child.name.prefix = #prefix;
-
-                        let child = std::rc::Rc::new(std::cell::RefCell::new(child));
-                        let mut child = easy_xml::XmlElement::Node(child);
-                        #field_name.serialize(&mut child);
-                        node.borrow_mut().elements.push(child);
-                      }
-                    }
+              }
+            })
+        })
+        .collect()
+}
+
+// 混合内容：文本项直接写入当前节点，其余写为子节点
+pub fn se_build_code_for_mixed(fields: &Vec<Field>) -> TokenStream {
+    fields
+        .iter()
+        .filter(|f| f.attrs.mixed)
+        .map(|f| {
+            let items = f.se_repeated_items();
+            f.se_guard(quote! {
+              for item in #items {
+                let mut child = easy_xml::XmlElement::Node(easy_xml::XmlNode::empty().into_ref());
+                item.serialize(&mut child);
+                if let easy_xml::XmlElement::Node(child_node) = &child {
+                  // 文本项不会设置节点名称
+                  if child_node.borrow().name.local_name.is_empty() {
+                    let elements = std::mem::take(&mut child_node.borrow_mut().elements);
+                    node.borrow_mut().elements.extend(elements);
+                    continue;
+                  }
                 }
-            }
+                node.borrow_mut().elements.push(child);
+              }
+            })
+        })
+        .collect()
+}
+
+pub fn se_build_code_for_node(fields: &Vec<Field>) -> TokenStream {
+    let code: TokenStream = fields
+        .into_iter()
+        .filter(|f| f.is_child())
+        .map(|f| f.se_guard(se_code_for_child(f)))
+        .collect();
+    // 反序列化时未匹配的子节点追加在最后
+    let code_other: TokenStream = fields
+        .iter()
+        .filter(|f| f.attrs.other_elements)
+        .map(|f| {
+            let items = f.se_repeated_items();
+            f.se_guard(quote! {
+              node.borrow_mut().elements.extend(#items.cloned());
+            })
         })
         .collect();
     quote! {
       #code
+      #code_other
+    }
+}
+
+// 子节点字段的序列化代码
+fn se_code_for_child(f: &Field) -> TokenStream {
+    let field_name = f.field_name();
+
+    let local_name = match &f.attrs.rename {
+        // 节点名称由子节点自己决定
+        Some(rename) => match f.multi_tag() {
+            true => quote! {
+              "".to_string()
+            },
+            false => quote! {
+              #rename.to_string()
+            },
+        },
+        None => match f.field.ident.as_ref() {
+            Some(ident) => {
+                let ident = ident.to_string();
+                quote! {
+                  #ident.to_string()
+                }
+            }
+            // 按顺序匹配的元组字段，节点名称由子节点自己决定
+            None => quote! {
+              "".to_string()
+            },
+        },
+    };
+    let prefix = match &f.attrs.prefix {
+        Some(prefix) => quote! {
+          Some(#prefix.to_string())
+        },
+        None => quote! {None},
+    };
+    if let Some(key) = &f.attrs.map {
+        f.se_map(key, local_name, prefix)
+    } else if f.is_repeated() {
+        let items = f.se_repeated_items();
+        let serialize = f.se_item_stmt(quote! {item}, quote! {&mut child});
+        if f.attrs.container {
+            // Option<Vec<T>>为Some时即使没有项也写出container
+            let present = match f.is_optional() {
+                true => quote! {#field_name.is_some()},
+                false => quote! {#field_name.len() > 0},
+            };
+            quote! {
+              {
+                if #present {
+                  let mut container = easy_xml::XmlNode::empty();
+                  container.name.local_name = #local_name;
+                  container.name.prefix = #prefix;
+                  for item in #items {
+                    let mut child = easy_xml::XmlNode::empty();
+                    let mut child = easy_xml::XmlElement::Node(child.into_ref());
+                    #serialize
+                    container.elements.push(child);
+                  }
+                  let mut container = easy_xml::XmlElement::Node(container.into_ref());
+                  node.borrow_mut().elements.push(container);
+                }
+              }
+            }
+        } else {
+            quote! {
+              {
+                for item in #items {
+                  let mut child = easy_xml::XmlNode::empty();
+                  child.name.local_name = #local_name;
+                  child.name.prefix = #prefix;
+
+                  let mut child = easy_xml::XmlElement::Node(child.into_ref());
+                  #serialize
+                  node.borrow_mut().elements.push(child);
+                }
+              }
+            }
+        }
+    } else {
+        let present = f.se_present();
+        let serialize = f.se_item_stmt(field_name, quote! {&mut child});
+        quote! {
+          if #present {
+            let mut child = easy_xml::XmlNode::empty();
+            child.name.local_name = #local_name;
+            child.name.prefix = #prefix;
+
+            let mut child = easy_xml::XmlElement::Node(child.into_ref());
+            #serialize
+            node.borrow_mut().elements.push(child);
+          }
+        }
     }
 }
 
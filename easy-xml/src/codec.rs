@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+// 读写使用的编码。WHATWG把ISO-8859-1、US-ASCII都当作windows-1252，
+// 这两种编码单独实现，其余交给encoding_rs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Codec {
+    Latin1,
+    Ascii,
+    Whatwg(&'static Encoding),
+}
+
+const LATIN1_LABELS: &[&str] = &[
+    "iso-8859-1",
+    "iso8859-1",
+    "iso_8859-1",
+    "iso_8859-1:1987",
+    "iso-ir-100",
+    "latin1",
+    "l1",
+    "cp819",
+    "ibm819",
+    "csisolatin1",
+];
+
+const ASCII_LABELS: &[&str] = &[
+    "us-ascii",
+    "ascii",
+    "us",
+    "iso646-us",
+    "iso-ir-6",
+    "ansi_x3.4-1968",
+    "cp367",
+    "ibm367",
+    "csascii",
+];
+
+impl Codec {
+    pub const UTF_8: Codec = Codec::Whatwg(UTF_8);
+
+    pub fn for_label(label: &str) -> Option<Codec> {
+        let label = label.trim();
+        if LATIN1_LABELS.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            return Some(Codec::Latin1);
+        }
+        if ASCII_LABELS.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            return Some(Codec::Ascii);
+        }
+        // hz-gb-2312、iso-2022-kr等映射到replacement编码，输出时实际为UTF-8，不支持
+        Encoding::for_label(label.as_bytes())
+            .map(Codec::Whatwg)
+            .filter(|codec| match codec {
+                Codec::Whatwg(encoding) => {
+                    encoding.output_encoding() == *encoding || codec.is_utf16()
+                }
+                _ => true,
+            })
+    }
+
+    pub fn is_utf16(&self) -> bool {
+        *self == Codec::Whatwg(UTF_16LE) || *self == Codec::Whatwg(UTF_16BE)
+    }
+
+    // 字符能否用该编码表示
+    pub fn can_encode(&self, c: char) -> bool {
+        match self {
+            Codec::Latin1 => (c as u32) <= 0xFF,
+            Codec::Ascii => c.is_ascii(),
+            Codec::Whatwg(encoding) => {
+                if c.is_ascii() || *encoding == UTF_8 || self.is_utf16() {
+                    return true;
+                }
+                let mut buf = [0u8; 4];
+                let (_, _, had_errors) = encoding.encode(c.encode_utf8(&mut buf));
+                !had_errors
+            }
+        }
+    }
+
+    // text中的字符都能用该编码表示，UTF-16按规范写入BOM
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        match self {
+            Codec::Latin1 => text.chars().map(|c| c as u8).collect(),
+            Codec::Ascii => text.as_bytes().to_vec(),
+            Codec::Whatwg(encoding) if *encoding == UTF_8 => text.as_bytes().to_vec(),
+            // encoding_rs 不会编码成 UTF-16，需要单独处理
+            Codec::Whatwg(encoding) if self.is_utf16() => {
+                let mut bytes = Vec::with_capacity(text.len() * 2 + 2);
+                for unit in std::iter::once(0xFEFF).chain(text.encode_utf16()) {
+                    if *encoding == UTF_16LE {
+                        bytes.extend_from_slice(&unit.to_le_bytes());
+                    } else {
+                        bytes.extend_from_slice(&unit.to_be_bytes());
+                    }
+                }
+                bytes
+            }
+            Codec::Whatwg(encoding) => encoding.encode(text).0.into_owned(),
+        }
+    }
+
+    // 不处理BOM，含有无效字节时返回None
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Option<Cow<'a, str>> {
+        match self {
+            Codec::Latin1 => Some(Cow::Owned(bytes.iter().map(|b| *b as char).collect())),
+            Codec::Ascii => match bytes.is_ascii() {
+                true => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+                false => None,
+            },
+            Codec::Whatwg(encoding) => {
+                encoding.decode_without_bom_handling_and_without_replacement(bytes)
+            }
+        }
+    }
+}
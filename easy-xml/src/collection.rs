@@ -0,0 +1,151 @@
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    convert::TryFrom,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{de, se, XmlDeserialize, XmlElement, XmlSerialize};
+
+// 可作为重复节点或list字段的集合类型
+pub trait XmlCollection: Sized {
+    type Item;
+
+    // 由按文档顺序解析出的所有项构造集合
+    fn from_items(items: Vec<Self::Item>) -> Result<Self, de::Error>;
+
+    // 序列化时遍历所有项
+    fn items(&self) -> Box<dyn Iterator<Item = &Self::Item> + '_>;
+}
+
+impl<T> XmlCollection for Vec<T> {
+    type Item = T;
+
+    fn from_items(items: Vec<T>) -> Result<Self, de::Error> {
+        Ok(items)
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<T> XmlCollection for VecDeque<T> {
+    type Item = T;
+
+    fn from_items(items: Vec<T>) -> Result<Self, de::Error> {
+        Ok(items.into())
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> XmlCollection for HashSet<T, S> {
+    type Item = T;
+
+    fn from_items(items: Vec<T>) -> Result<Self, de::Error> {
+        Ok(items.into_iter().collect())
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<T: Ord> XmlCollection for BTreeSet<T> {
+    type Item = T;
+
+    fn from_items(items: Vec<T>) -> Result<Self, de::Error> {
+        Ok(items.into_iter().collect())
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+impl<T> XmlCollection for Box<[T]> {
+    type Item = T;
+
+    fn from_items(items: Vec<T>) -> Result<Self, de::Error> {
+        Ok(items.into_boxed_slice())
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+// 定长数组，项数不一致时报错
+impl<T, const N: usize> XmlCollection for [T; N] {
+    type Item = T;
+
+    fn from_items(items: Vec<T>) -> Result<Self, de::Error> {
+        let len = items.len();
+        <[T; N]>::try_from(items)
+            .map_err(|_| de::Error::Other(format!("Expected {} items, found {}!", N, len)))
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array> XmlCollection for smallvec::SmallVec<A> {
+    type Item = A::Item;
+
+    fn from_items(items: Vec<A::Item>) -> Result<Self, de::Error> {
+        Ok(smallvec::SmallVec::from_vec(items))
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = &A::Item> + '_> {
+        Box::new(self.iter())
+    }
+}
+
+// 作为单个节点的值时，节点下的每个子节点为一项，缺少时为空集合
+macro_rules! impl_xml_for_collection {
+    ($ty:ty, [$($generics:tt)*]) => {
+        impl<$($generics)*> XmlDeserialize for $ty
+        where
+            <$ty as XmlCollection>::Item: XmlDeserialize,
+        {
+            fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+            where
+                Self: Sized,
+            {
+                de::items_from_element(element, None)
+            }
+
+            fn absent() -> Option<Self>
+            where
+                Self: Sized,
+            {
+                <$ty as XmlCollection>::from_items(Vec::new()).ok()
+            }
+        }
+
+        impl<$($generics)*> XmlSerialize for $ty
+        where
+            <$ty as XmlCollection>::Item: XmlSerialize,
+        {
+            fn serialize(&self, element: &mut XmlElement)
+            where
+                Self: Sized,
+            {
+                se::items_to_element(self, None, element);
+            }
+        }
+    };
+}
+
+impl_xml_for_collection!(Vec<T>, [T]);
+impl_xml_for_collection!(VecDeque<T>, [T]);
+impl_xml_for_collection!(HashSet<T, S>, [T: Eq + Hash, S: BuildHasher + Default]);
+impl_xml_for_collection!(BTreeSet<T>, [T: Ord]);
+impl_xml_for_collection!(Box<[T]>, [T]);
+impl_xml_for_collection!([T; N], [T, const N: usize]);
+#[cfg(feature = "smallvec")]
+impl_xml_for_collection!(smallvec::SmallVec<A>, [A: smallvec::Array]);
@@ -1,14 +1,37 @@
-use std::{cell::RefCell, io::Read, rc::Rc};
+use std::{
+    collections::HashMap, fmt::Display, io::Read, marker::PhantomData, ops::Range, str::FromStr,
+    sync::OnceLock,
+};
 
-use crate::{XmlDeserialize, XmlDocument, XmlElement, XmlNode};
-use xml::reader::{EventReader, XmlEvent};
+use crate::{
+    codec::Codec,
+    format::{self, ValueFormat},
+    raw::{self, SourceMap},
+    OwnedName, XmlCollection, XmlDeserialize, XmlDocument, XmlElement, XmlNode, XmlNodeRef,
+};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use xml::reader::{EventReader, ParserConfig2, XmlEvent};
 
-fn parse_xml<R: Read>(reader: EventReader<R>) -> xml::reader::Result<XmlDocument> {
+// spans不为None时，记录每个节点在reader读取的文本中的位置
+fn parse_xml<R: Read>(
+    mut reader: EventReader<MarkupTracker<R>>,
+    options: &ParseOptions,
+    mut spans: Option<&mut SpanTracker>,
+) -> Result<XmlDocument, Error> {
     let mut doc = None;
 
-    let mut stack = Vec::new();
-    for e in reader {
-        let e = e?;
+    let mut stack: Vec<XmlNodeRef> = Vec::new();
+    let mut nodes = 0;
+    loop {
+        let e = match reader.next() {
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(e) => e,
+            Err(e) => return Err(xml_error(e, reader.source())),
+        };
+        if let XmlEvent::StartElement { .. } = e {
+            nodes += 1;
+        }
+        check_limits(&e, stack.len(), nodes, options)?;
         match e {
             XmlEvent::StartDocument {
                 version,
@@ -22,103 +45,1135 @@ fn parse_xml<R: Read>(reader: EventReader<R>) -> xml::reader::Result<XmlDocument
                     elements: vec![],
                 });
             }
-            XmlEvent::StartElement {
+            XmlEvent::StartElement { ref name, .. } => {
+                if let Some(spans) = &mut spans {
+                    spans.start(name);
+                }
+                push_event(&mut stack, e);
+            }
+            XmlEvent::EndElement { .. } => {
+                if let (Some(spans), Some(node)) = (&mut spans, stack.last()) {
+                    spans.end(node);
+                }
+                if let Some(element) = push_event(&mut stack, e) {
+                    doc.as_mut().unwrap().elements.push(element);
+                }
+            }
+            e => {
+                if let Some(element) = push_event(&mut stack, e) {
+                    (&mut doc).as_mut().unwrap().elements.push(element);
+                }
+            }
+        }
+    }
+
+    return Ok(doc.unwrap());
+}
+
+// 将解析事件转换成节点挂到栈顶节点下，栈为空时返回已完成的顶层元素
+fn push_event(stack: &mut Vec<XmlNodeRef>, e: XmlEvent) -> Option<XmlElement> {
+    let element = match e {
+        XmlEvent::StartElement {
+            name,
+            attributes,
+            namespace,
+        } => {
+            let node = XmlNode {
                 name,
                 attributes,
                 namespace,
-            } => {
-                let node = XmlNode {
-                    name,
-                    attributes,
-                    namespace,
-                    elements: vec![],
-                    parent: None,
-                };
-                let node = Rc::new(RefCell::new(node));
-                stack.push(node);
-            }
-            XmlEvent::EndElement { name: _ } => {
-                let node = stack.pop().unwrap();
-                add_element_to_parent(
-                    XmlElement::Node(node),
-                    &mut stack,
-                    (&mut doc).as_mut().unwrap(),
-                );
-            }
-            XmlEvent::Characters(s) => {
-                add_element_to_parent(
-                    XmlElement::Text(s),
-                    &mut stack,
-                    (&mut doc).as_mut().unwrap(),
-                );
-            }
-            XmlEvent::Comment(s) => {
-                add_element_to_parent(
-                    XmlElement::Comment(s),
-                    &mut stack,
-                    (&mut doc).as_mut().unwrap(),
-                );
-            }
-            XmlEvent::CData(s) => {
-                add_element_to_parent(
-                    XmlElement::CData(s),
-                    &mut stack,
-                    (&mut doc).as_mut().unwrap(),
-                );
-            }
-            XmlEvent::Whitespace(s) => {
-                add_element_to_parent(
-                    XmlElement::Whitespace(s),
-                    &mut stack,
-                    (&mut doc).as_mut().unwrap(),
-                );
+                elements: vec![],
+                parent: None,
+            };
+            stack.push(node.into_ref());
+            return None;
+        }
+        XmlEvent::EndElement { name: _ } => XmlElement::Node(stack.pop()?),
+        XmlEvent::Characters(s) => XmlElement::Text(s),
+        XmlEvent::Comment(s) => XmlElement::Comment(s),
+        XmlEvent::CData(s) => XmlElement::CData(s),
+        XmlEvent::Whitespace(s) => XmlElement::Whitespace(s),
+        _ => return None,
+    };
+    add_element_to_parent(element, stack)
+}
+
+// 按文档顺序在源文本中查找开始、结束事件对应的标签，得到节点内部的范围。
+// 标签与事件对不上时（如实体展开出的节点）不再记录
+struct SpanTracker<'a> {
+    source: &'a str,
+    pos: usize,
+    // 未关闭节点内部的起始位置，以及是否为自闭合标签
+    open: Vec<(usize, bool)>,
+    failed: bool,
+    // 以节点的地址为键
+    spans: HashMap<usize, Range<usize>>,
+}
+
+impl<'a> SpanTracker<'a> {
+    fn new(source: &'a str) -> Self {
+        SpanTracker {
+            source,
+            pos: 0,
+            open: Vec::new(),
+            failed: false,
+            spans: HashMap::new(),
+        }
+    }
+
+    fn start(&mut self, name: &OwnedName) {
+        match self.next_tag() {
+            Some((Markup::Start, start, end)) if self.tag_is(start + 1, name) => {
+                self.open.push((end, false))
             }
-            _ => {}
+            Some((Markup::Empty, start, end)) if self.tag_is(start + 1, name) => {
+                self.open.push((end, true))
+            }
+            _ => self.failed = true,
         }
     }
 
-    return Ok(doc.unwrap());
+    fn end(&mut self, node: &XmlNodeRef) {
+        if self.failed {
+            return;
+        }
+        let (inner_start, empty) = match self.open.pop() {
+            Some(open) => open,
+            None => return,
+        };
+        let inner_end = match empty {
+            true => inner_start,
+            false => match self.next_tag() {
+                Some((Markup::End, start, _)) => start,
+                _ => {
+                    self.failed = true;
+                    return;
+                }
+            },
+        };
+        self.spans
+            .insert(raw::node_key(node), inner_start..inner_end);
+    }
+
+    // 跳过注释、CDATA等，返回下一个标签的类型、起止位置
+    fn next_tag(&mut self) -> Option<(Markup, usize, usize)> {
+        if self.failed {
+            return None;
+        }
+        let bytes = self.source.as_bytes();
+        while let Some(start) = find(bytes, self.pos, b"<") {
+            let (markup, end) = scan_markup(bytes, start)?;
+            self.pos = end;
+            match markup {
+                Markup::Other => continue,
+                markup => return Some((markup, start, end)),
+            }
+        }
+        None
+    }
+
+    fn tag_is(&self, from: usize, name: &OwnedName) -> bool {
+        let tag = tag_name(&self.source.as_bytes()[from..]);
+        match &name.prefix {
+            Some(prefix) => {
+                tag.len() == prefix.len() + 1 + name.local_name.len()
+                    && tag.starts_with(prefix.as_bytes())
+                    && tag[prefix.len()] == b':'
+                    && tag.ends_with(name.local_name.as_bytes())
+            }
+            None => tag == name.local_name.as_bytes(),
+        }
+    }
 }
-fn add_element_to_parent(
-    node: XmlElement,
-    stack: &mut Vec<Rc<RefCell<XmlNode>>>,
-    doc: &mut XmlDocument,
-) {
-    if stack.len() > 0 {
-        let idx = stack.len() - 1;
-        let parent = stack.get_mut(idx).unwrap();
-        match &node {
-            XmlElement::Node(node) => {
-                node.borrow_mut().parent = Some(Rc::downgrade(parent));
+
+fn add_element_to_parent(node: XmlElement, stack: &mut Vec<XmlNodeRef>) -> Option<XmlElement> {
+    match stack.last() {
+        Some(parent) => {
+            if let XmlElement::Node(node) = &node {
+                node.borrow_mut().parent = Some(XmlNodeRef::downgrade(parent));
             }
-            _ => {}
+            parent.borrow_mut().elements.push(node);
+            None
         }
-        (*parent.borrow_mut()).elements.push(node);
-    } else {
-        doc.elements.push(node);
+        None => Some(node),
+    }
+}
+
+// 检查事件是否超出限制，depth为事件所在的嵌套深度，nodes为已读取的节点数
+fn check_limits(
+    e: &XmlEvent,
+    depth: usize,
+    nodes: usize,
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    match e {
+        XmlEvent::StartElement { attributes, .. } => {
+            if depth >= options.max_depth {
+                return Err(Error::LimitExceeded(Limit::Depth));
+            }
+            if attributes.len() > options.max_attributes {
+                return Err(Error::LimitExceeded(Limit::Attributes));
+            }
+            if nodes > options.max_nodes {
+                return Err(Error::LimitExceeded(Limit::Nodes));
+            }
+            if attributes
+                .iter()
+                .any(|attr| attr.value.len() > options.max_text_len)
+            {
+                return Err(Error::LimitExceeded(Limit::TextLength));
+            }
+        }
+        XmlEvent::Characters(s)
+        | XmlEvent::CData(s)
+        | XmlEvent::Comment(s)
+        | XmlEvent::Whitespace(s)
+            if s.len() > options.max_text_len =>
+        {
+            return Err(Error::LimitExceeded(Limit::TextLength));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// 属性数量、文本、属性值长度及实体展开由xml-rs在读取时限制，不必读完整个标签或整段数据
+fn parser_config(options: &ParseOptions) -> ParserConfig2 {
+    ParserConfig2::new()
+        .ignore_comments(false)
+        .max_attributes(options.max_attributes)
+        .max_attribute_length(options.max_text_len)
+        .max_data_length(options.max_text_len)
+        .max_entity_expansion_length(options.max_entity_expansion)
+}
+
+fn create_reader<R: Read>(config: ParserConfig2, source: R) -> EventReader<MarkupTracker<R>> {
+    config.create_reader(MarkupTracker::new(source))
+}
+
+// xml-rs 没有公开超出限制的错误类型，只能与同样超出限制时产生的错误比较ErrorKind，
+// 实际比较的是错误信息。属性数量、文本及属性值长度超出时错误相同，
+// 由读取位置是否在开始标签内、属性值之外区分
+fn xml_error<R>(e: xml::reader::Error, tracker: &MarkupTracker<R>) -> Error {
+    // 探测用的错误只生成一次
+    static PROBES: OnceLock<Vec<(Limit, xml::reader::Error)>> = OnceLock::new();
+    let probes = PROBES.get_or_init(|| {
+        let limits = vec![
+            (
+                Limit::TextLength,
+                ParserConfig2::new().max_data_length(1),
+                "<a>text</a>",
+            ),
+            (
+                Limit::EntityExpansion,
+                ParserConfig2::new().max_entity_expansion_length(1),
+                r#"<!DOCTYPE a [<!ENTITY e "text"><!ENTITY f "&e;&e;">]><a>&f;</a>"#,
+            ),
+        ];
+        limits
+            .into_iter()
+            .filter_map(|(limit, config, xml)| {
+                let probe = config
+                    .create_reader(xml.as_bytes())
+                    .into_iter()
+                    .find_map(|e| e.err())?;
+                Some((limit, probe))
+            })
+            .collect()
+    });
+    for (limit, probe) in probes {
+        if probe.kind() == e.kind() {
+            return match limit {
+                Limit::TextLength if tracker.in_attributes() => {
+                    Error::LimitExceeded(Limit::Attributes)
+                }
+                limit => Error::LimitExceeded(*limit),
+            };
+        }
+    }
+    Error::XmlError(e)
+}
+
+// 传给xml-rs的输入，按字节跟踪读取位置所在的标记，只用于区分超出限制的原因。
+// xml-rs逐字节读取，读到的位置即解析到的位置
+struct MarkupTracker<R> {
+    inner: R,
+    state: MarkupState,
+    // 最近读取的3个字节，用于匹配结束符
+    recent: [u8; 3],
+}
+
+#[derive(Clone, Copy)]
+enum MarkupState {
+    Content,
+    // 刚读到<
+    Open,
+    // 刚读到<!
+    Bang,
+    // 开始标签内，记录所在属性值的引号
+    StartTag(Option<u8>),
+    // 注释、CDATA、处理指令及结束标签，直到结束符
+    Until(&'static [u8]),
+    Doctype { quote: Option<u8>, depth: usize },
+}
+
+impl<R> MarkupTracker<R> {
+    fn new(inner: R) -> Self {
+        MarkupTracker {
+            inner,
+            state: MarkupState::Content,
+            recent: [0; 3],
+        }
+    }
+
+    // 是否在开始标签内、属性值之外
+    fn in_attributes(&self) -> bool {
+        matches!(self.state, MarkupState::StartTag(None))
+    }
+
+    fn scan(&mut self, b: u8) {
+        self.recent = [self.recent[1], self.recent[2], b];
+        self.state = match (self.state, b) {
+            (MarkupState::Content, b'<') => MarkupState::Open,
+            (MarkupState::Content, _) => MarkupState::Content,
+            (MarkupState::Open, b'!') => MarkupState::Bang,
+            (MarkupState::Open, b'?') => MarkupState::Until(b"?>"),
+            (MarkupState::Open, b'/') => MarkupState::Until(b">"),
+            (MarkupState::Open, _) => MarkupState::StartTag(None),
+            (MarkupState::Bang, b'-') => MarkupState::Until(b"-->"),
+            (MarkupState::Bang, b'[') => MarkupState::Until(b"]]>"),
+            (MarkupState::Bang, _) => MarkupState::Doctype {
+                quote: None,
+                depth: 0,
+            },
+            (MarkupState::StartTag(None), b'>') => MarkupState::Content,
+            (MarkupState::StartTag(None), b'"' | b'\'') => MarkupState::StartTag(Some(b)),
+            (MarkupState::StartTag(Some(q)), b) if q == b => MarkupState::StartTag(None),
+            (MarkupState::Until(end), _) if self.recent.ends_with(end) => MarkupState::Content,
+            (MarkupState::Doctype { quote: None, depth }, b) => match b {
+                b'"' | b'\'' => MarkupState::Doctype {
+                    quote: Some(b),
+                    depth,
+                },
+                b'[' => MarkupState::Doctype {
+                    quote: None,
+                    depth: depth + 1,
+                },
+                b']' => MarkupState::Doctype {
+                    quote: None,
+                    depth: depth.saturating_sub(1),
+                },
+                b'>' if depth == 0 => MarkupState::Content,
+                _ => self.state,
+            },
+            (
+                MarkupState::Doctype {
+                    quote: Some(q),
+                    depth,
+                },
+                b,
+            ) if q == b => MarkupState::Doctype { quote: None, depth },
+            (state, _) => state,
+        };
+    }
+}
+
+impl<R: Read> Read for MarkupTracker<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        for b in &buf[..n] {
+            self.scan(*b);
+        }
+        Ok(n)
     }
 }
 
 pub fn from_str<T: XmlDeserialize>(xml: &str) -> Result<T, Error> {
-    return from_bytes(xml.as_bytes());
+    from_str_with_options(xml, &ParseOptions::default())
 }
+
+pub fn from_str_with_options<T: XmlDeserialize>(
+    xml: &str,
+    options: &ParseOptions,
+) -> Result<T, Error> {
+    // 字符串已经是UTF-8，忽略声明中的编码
+    let options = ParseOptions {
+        encoding: Some("UTF-8".to_string()),
+        ..options.clone()
+    };
+    from_bytes_with_options(xml.as_bytes(), &options)
+}
+
 pub fn from_bytes<T: XmlDeserialize, R: Read>(source: R) -> Result<T, Error> {
-    let reader = EventReader::new(source);
+    from_bytes_with_options(source, &ParseOptions::default())
+}
+
+pub fn from_bytes_with_options<T: XmlDeserialize, R: Read>(
+    source: R,
+    options: &ParseOptions,
+) -> Result<T, Error> {
+    let (doc, source) = parse_document_with_source(source, options)?;
+    raw::with_source(source, || {
+        format::with_format(options.format.clone(), || from_document(&doc))
+    })
+}
+
+// 解析选项，默认限制适用于不可信的输入
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    // 指定输入编码，优先于BOM及声明中的encoding
+    pub encoding: Option<String>,
+    // 节点最大嵌套深度，同时限制了反序列化时的递归深度
+    pub max_depth: usize,
+    // 单个节点的最大属性数量
+    pub max_attributes: usize,
+    // 单段文本、CDATA、注释及属性值的最大长度
+    pub max_text_len: usize,
+    // 节点总数
+    pub max_nodes: usize,
+    // 实体展开后的最大长度
+    pub max_entity_expansion: usize,
+    // 推送式解析时缓存的未接收完整的子节点或标记的最大字节数
+    pub max_pending_len: usize,
+    // bool和数字的文本格式
+    pub format: ValueFormat,
+    // 反序列化期间保留源文本，RawXml字段取得与输入逐字节相同的内部XML。
+    // 需要读入全部输入，只对from_str_with_options、from_bytes_with_options有效
+    pub keep_source: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            encoding: None,
+            max_depth: 256,
+            max_attributes: 256,
+            max_text_len: 16 * 1024 * 1024,
+            max_nodes: 2_000_000,
+            max_entity_expansion: 64 * 1024,
+            max_pending_len: 64 * 1024 * 1024,
+            format: ValueFormat::default(),
+            keep_source: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Depth,
+    Attributes,
+    TextLength,
+    Nodes,
+    EntityExpansion,
+    PendingLength,
+}
 
-    let doc = match parse_xml(reader) {
-        Ok(doc) => doc,
-        Err(_) => return Err(Error::BadXml),
+// 只解析出节点树，便于缓存或在其他线程中反序列化
+pub fn parse_document<R: Read>(source: R) -> Result<XmlDocument, Error> {
+    parse_document_with_options(source, &ParseOptions::default())
+}
+
+// 根据BOM及声明检测编码，转换成UTF-8后再解析，检测到的编码记录在XmlDocument.encoding中
+pub fn parse_document_with_options<R: Read>(
+    source: R,
+    options: &ParseOptions,
+) -> Result<XmlDocument, Error> {
+    parse_document_with_source(source, options).map(|(doc, _)| doc)
+}
+
+// options.keep_source为true时同时返回源文本及每个节点内部的范围
+fn parse_document_with_source<R: Read>(
+    mut source: R,
+    options: &ParseOptions,
+) -> Result<(XmlDocument, Option<SourceMap>), Error> {
+    let mut prefix = Vec::with_capacity(1024);
+    if let Err(e) = (&mut source).take(1024).read_to_end(&mut prefix) {
+        return Err(Error::IoError(e));
+    }
+    let (encoding, label, bom) = detect_encoding(&prefix, options)?;
+
+    // 已统一转换成UTF-8，忽略声明中的编码
+    let config = parser_config(options)
+        .override_encoding(Some(xml::Encoding::Utf8))
+        .ignore_invalid_encoding_declarations(true);
+
+    let doc = if encoding == Codec::UTF_8 && !options.keep_source {
+        let reader = create_reader(config, (&prefix[bom..]).chain(source));
+        parse_xml(reader, options, None).map(|doc| (doc, None))
+    } else {
+        if let Err(e) = source.read_to_end(&mut prefix) {
+            return Err(Error::IoError(e));
+        }
+        let text = match encoding.decode(&prefix[bom..]) {
+            Some(text) => text.into_owned(),
+            None if encoding == Codec::UTF_8 => return Err(Error::BadXml),
+            None => return Err(Error::MalformedEncoding(label)),
+        };
+        drop(prefix);
+
+        let reader = create_reader(config, text.as_bytes());
+        match options.keep_source {
+            true => {
+                let mut spans = SpanTracker::new(&text);
+                let doc = parse_xml(reader, options, Some(&mut spans));
+                let spans = spans.spans;
+                doc.map(|doc| (doc, Some(SourceMap::new(text, spans))))
+            }
+            false => parse_xml(reader, options, None).map(|doc| (doc, None)),
+        }
     };
 
-    match doc.elements.get(0) {
+    match doc {
+        Ok((mut doc, source)) => {
+            doc.encoding = label;
+            Ok((doc, source))
+        }
+        Err(Error::XmlError(_)) => Err(Error::BadXml),
+        Err(e) => Err(e),
+    }
+}
+
+// 返回编码、编码名称及BOM长度。指定编码优先，其次是BOM，最后是声明
+fn detect_encoding(prefix: &[u8], options: &ParseOptions) -> Result<(Codec, String, usize), Error> {
+    let bom = Encoding::for_bom(prefix);
+
+    let label = match (&options.encoding, bom) {
+        (Some(label), _) => label.trim().to_string(),
+        (None, Some((encoding, len))) => {
+            return Ok((Codec::Whatwg(encoding), encoding.name().to_string(), len))
+        }
+        (None, None) => {
+            // 没有BOM的UTF-16
+            if prefix.starts_with(&[b'<', 0, b'?', 0]) {
+                return Ok((Codec::Whatwg(UTF_16LE), UTF_16LE.name().to_string(), 0));
+            }
+            if prefix.starts_with(&[0, b'<', 0, b'?']) {
+                return Ok((Codec::Whatwg(UTF_16BE), UTF_16BE.name().to_string(), 0));
+            }
+            match declared_encoding(prefix) {
+                Some(label) => label,
+                None => return Ok((Codec::UTF_8, UTF_8.name().to_string(), 0)),
+            }
+        }
+    };
+
+    let encoding = match Codec::for_label(&label) {
+        Some(encoding) => encoding,
+        None => return Err(Error::UnsupportedEncoding(label)),
+    };
+    // 声明能按单字节读出，实际内容不可能是UTF-16
+    if options.encoding.is_none() && encoding.is_utf16() {
+        return Ok((Codec::UTF_8, UTF_8.name().to_string(), 0));
+    }
+    let bom_len = match bom {
+        Some((bom, len)) if Codec::Whatwg(bom) == encoding => len,
+        _ => 0,
+    };
+    Ok((encoding, label, bom_len))
+}
+
+// 读取 <?xml ... encoding="..."?> 中的编码
+fn declared_encoding(prefix: &[u8]) -> Option<String> {
+    if !prefix.starts_with(b"<?xml") {
+        return None;
+    }
+    let end = find(prefix, 0, b"?>")?;
+    let decl = &prefix[..end];
+    let start = find(decl, 0, b"encoding")? + b"encoding".len();
+
+    let rest = decl[start..].iter().skip_while(|c| c.is_ascii_whitespace());
+    let mut rest = rest.skip_while(|c| **c == b'=' || c.is_ascii_whitespace());
+    let quote = *rest.next()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let value = rest
+        .take_while(|c| **c != quote)
+        .cloned()
+        .collect::<Vec<_>>();
+    String::from_utf8(value).ok()
+}
+
+pub fn from_document<T: XmlDeserialize>(doc: &XmlDocument) -> Result<T, Error> {
+    // 根节点前可能有注释
+    match doc
+        .elements
+        .iter()
+        .find(|e| matches!(e, XmlElement::Node(_)))
+    {
         Some(root) => T::deserialize(root),
         None => return Err(Error::BadXml),
     }
 }
 
+// 逐条读取名称为tag的记录，匹配任意深度的节点，每次只构建一个记录节点的子树
+pub fn iter_from_reader<T: XmlDeserialize, R: Read>(reader: R, tag: &str) -> RecordIter<T, R> {
+    iter_from_reader_with_options(reader, tag, &ParseOptions::default())
+}
+
+// max_nodes按每条记录计算，其余限制与from_bytes_with_options相同
+pub fn iter_from_reader_with_options<T: XmlDeserialize, R: Read>(
+    reader: R,
+    tag: &str,
+    options: &ParseOptions,
+) -> RecordIter<T, R> {
+    RecordIter {
+        reader: create_reader(parser_config(options), reader),
+        tag: tag.to_string(),
+        options: options.clone(),
+        stack: Vec::new(),
+        depth: 0,
+        nodes: 0,
+        finished: false,
+        _marker: PhantomData,
+    }
+}
+
+pub struct RecordIter<T, R: Read> {
+    reader: EventReader<MarkupTracker<R>>,
+    tag: String,
+    options: ParseOptions,
+    stack: Vec<XmlNodeRef>,
+    // 当前事件在整个文档中的嵌套深度
+    depth: usize,
+    // 当前记录的节点数
+    nodes: usize,
+    finished: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T: XmlDeserialize, R: Read> Iterator for RecordIter<T, R> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let e = match self.reader.next() {
+                Ok(e) => e,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(xml_error(e, self.reader.source())));
+                }
+            };
+            let depth = self.depth;
+            match &e {
+                XmlEvent::StartElement { name, .. } => {
+                    self.depth += 1;
+                    if self.stack.is_empty() && name.local_name == self.tag {
+                        self.nodes = 0;
+                    }
+                    self.nodes += 1;
+                }
+                XmlEvent::EndElement { .. } => self.depth -= 1,
+                _ => {}
+            }
+            if let Err(err) = check_limits(&e, depth, self.nodes, &self.options) {
+                self.finished = true;
+                return Some(Err(err));
+            }
+            match &e {
+                XmlEvent::EndDocument => {
+                    self.finished = true;
+                    continue;
+                }
+                // 记录节点之外的内容直接跳过
+                XmlEvent::StartElement { name, .. }
+                    if self.stack.is_empty() && name.local_name != self.tag =>
+                {
+                    continue
+                }
+                XmlEvent::StartElement { .. } => {}
+                _ if self.stack.is_empty() => continue,
+                _ => {}
+            }
+            if let Some(element) = push_event(&mut self.stack, e) {
+                return Some(format::with_format(self.options.format.clone(), || {
+                    T::deserialize(&element)
+                }));
+            }
+        }
+        None
+    }
+}
+
+pub enum StreamEvent {
+    // 根节点开始标签，不包含子节点
+    Start(XmlElement),
+    // 根节点下已完整接收的子节点
+    Element(XmlElement),
+    // 根节点已关闭
+    End,
+}
+
+// 推送式解析器：按任意分块喂入数据，每当根节点下的一个子节点接收完整时返回
+pub struct Parser {
+    buffer: Vec<u8>,
+    // 已扫描到的位置
+    pos: usize,
+    depth: usize,
+    // 当前子节点在buffer中的起始位置
+    element_start: usize,
+    // 声明及根节点开始标签，用于补全子节点的命名空间上下文
+    header: Vec<u8>,
+    root_name: Vec<u8>,
+    closed: bool,
+    options: ParseOptions,
+}
+
+enum Markup {
+    Start,
+    Empty,
+    End,
+    Other,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Parser::with_options(&ParseOptions::default())
+    }
+
+    // 每个子节点单独解析，max_nodes按每个子节点计算
+    pub fn with_options(options: &ParseOptions) -> Self {
+        Parser {
+            buffer: Vec::new(),
+            pos: 0,
+            depth: 0,
+            element_start: 0,
+            header: Vec::new(),
+            root_name: Vec::new(),
+            closed: false,
+            // 解析子节点时包含了根节点
+            options: ParseOptions {
+                max_nodes: options.max_nodes.saturating_add(1),
+                ..options.clone()
+            },
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn feed(&mut self, data: &[u8]) -> Result<Vec<StreamEvent>, Error> {
+        let mut events = Vec::new();
+        if self.closed {
+            return Ok(events);
+        }
+        self.buffer.extend_from_slice(data);
+
+        loop {
+            let start = match find(&self.buffer, self.pos, b"<") {
+                Some(start) => start,
+                None => {
+                    // 剩余的都是文本，下次从末尾继续查找
+                    self.pos = self.buffer.len();
+                    break;
+                }
+            };
+            let (markup, end) = match scan_markup(&self.buffer, start) {
+                Some(markup) => markup,
+                None => {
+                    // 标记不完整，等待更多数据
+                    self.pos = start;
+                    break;
+                }
+            };
+            self.pos = end;
+
+            match (markup, self.depth) {
+                (Markup::Start, 0) => {
+                    self.header = self.buffer[..end].to_vec();
+                    self.root_name = tag_name(&self.buffer[start + 1..end]).to_vec();
+                    self.depth = 1;
+                    events.push(StreamEvent::Start(self.parse_fragment(&[])?));
+                }
+                (Markup::Empty, 0) => {
+                    self.header = self.buffer[..end].to_vec();
+                    events.push(StreamEvent::Start(parse_root(&self.header, &self.options)?));
+                    events.push(StreamEvent::End);
+                    self.closed = true;
+                    break;
+                }
+                (Markup::End, 0) => return Err(Error::BadXml),
+                (Markup::End, 1) => {
+                    events.push(StreamEvent::End);
+                    self.closed = true;
+                    break;
+                }
+                // 子节点接收完整前一直保留在buffer中，提前检查深度
+                (Markup::Start, depth) if depth >= self.options.max_depth => {
+                    return Err(Error::LimitExceeded(Limit::Depth));
+                }
+                (Markup::Start, 1) => {
+                    self.element_start = start;
+                    self.depth = 2;
+                }
+                (Markup::Empty, 1) => {
+                    let fragment = self.buffer[start..end].to_vec();
+                    events.push(StreamEvent::Element(self.parse_child(&fragment)?));
+                }
+                (Markup::Start, _) => self.depth += 1,
+                (Markup::End, _) => {
+                    self.depth -= 1;
+                    if self.depth == 1 {
+                        let fragment = self.buffer[self.element_start..end].to_vec();
+                        events.push(StreamEvent::Element(self.parse_child(&fragment)?));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // 丢弃已处理的数据
+        let drain = match self.depth {
+            0 | 1 => self.pos,
+            _ => self.element_start,
+        };
+        self.buffer.drain(..drain);
+        self.pos -= drain;
+        self.element_start -= drain.min(self.element_start);
+        if self.closed {
+            self.buffer.clear();
+        }
+        if self.buffer.len() > self.options.max_pending_len {
+            return Err(Error::LimitExceeded(Limit::PendingLength));
+        }
+
+        Ok(events)
+    }
+
+    fn parse_fragment(&self, fragment: &[u8]) -> Result<XmlElement, Error> {
+        let mut xml = self.header.clone();
+        xml.extend_from_slice(fragment);
+        xml.extend_from_slice(b"</");
+        xml.extend_from_slice(&self.root_name);
+        xml.push(b'>');
+        parse_root(&xml, &self.options)
+    }
+
+    fn parse_child(&self, fragment: &[u8]) -> Result<XmlElement, Error> {
+        let root = self.parse_fragment(fragment)?;
+        if let XmlElement::Node(root) = root {
+            for element in &root.borrow().elements {
+                if let XmlElement::Node(node) = element {
+                    node.borrow_mut().parent = None;
+                    return Ok(element.clone());
+                }
+            }
+        }
+        Err(Error::BadXml)
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser::new()
+    }
+}
+
+fn parse_root(xml: &[u8], options: &ParseOptions) -> Result<XmlElement, Error> {
+    let reader = create_reader(parser_config(options), xml);
+    let doc = parse_xml(reader, options, None)?;
+    for element in doc.elements {
+        if let XmlElement::Node(_) = element {
+            return Ok(element);
+        }
+    }
+    Err(Error::BadXml)
+}
+
+fn find(buffer: &[u8], from: usize, pat: &[u8]) -> Option<usize> {
+    if from >= buffer.len() {
+        return None;
+    }
+    buffer[from..]
+        .windows(pat.len())
+        .position(|w| w == pat)
+        .map(|i| i + from)
+}
+
+// 扫描从start开始的标记，返回标记类型及结束位置，数据不完整时返回None
+fn scan_markup(buffer: &[u8], start: usize) -> Option<(Markup, usize)> {
+    let rest = &buffer[start..];
+    if rest.starts_with(b"<?") {
+        return Some((Markup::Other, find(buffer, start, b"?>")? + 2));
+    }
+    if rest.starts_with(b"<!--") {
+        return Some((Markup::Other, find(buffer, start + 4, b"-->")? + 3));
+    }
+    if rest.starts_with(b"<![CDATA[") {
+        return Some((Markup::Other, find(buffer, start, b"]]>")? + 3));
+    }
+    if rest.starts_with(b"<!") {
+        // DOCTYPE，可能带有内部子集
+        let end = match find(buffer, start, b"[") {
+            Some(i) if i < find(buffer, start, b">")? => find(buffer, i, b"]")?,
+            _ => start,
+        };
+        return Some((Markup::Other, find(buffer, end, b">")? + 1));
+    }
+    if rest.len() < 2 {
+        return None;
+    }
+
+    let mut quote = None;
+    for (i, c) in rest.iter().enumerate().skip(1) {
+        match (quote, *c) {
+            (None, b'"') | (None, b'\'') => quote = Some(*c),
+            (Some(q), c) if q == c => quote = None,
+            (None, b'>') => {
+                let markup = if rest[1] == b'/' {
+                    Markup::End
+                } else if rest[i - 1] == b'/' {
+                    Markup::Empty
+                } else {
+                    Markup::Start
+                };
+                return Some((markup, start + i + 1));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn tag_name(tag: &[u8]) -> &[u8] {
+    let end = tag
+        .iter()
+        .position(|c| c.is_ascii_whitespace() || *c == b'/' || *c == b'>')
+        .unwrap_or(tag.len());
+    &tag[..end]
+}
+
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader<T, R>(mut reader: R) -> Result<T, Error>
+where
+    T: XmlDeserialize,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut bytes = Vec::new();
+    if let Err(e) = reader.read_to_end(&mut bytes).await {
+        return Err(Error::IoError(e));
+    }
+    from_bytes(bytes.as_slice())
+}
+
+// 异步逐条读取名称为tag的记录，与iter_from_reader一样匹配任意深度的节点，
+// 但根节点的每个子节点接收完整后才查找其中的记录
+#[cfg(feature = "tokio")]
+pub fn iter_from_async_reader<T, R>(reader: R, tag: &str) -> AsyncRecordReader<T, R>
+where
+    T: XmlDeserialize,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    iter_from_async_reader_with_options(reader, tag, &ParseOptions::default())
+}
+
+// max_nodes按根节点的每个子节点计算
+#[cfg(feature = "tokio")]
+pub fn iter_from_async_reader_with_options<T, R>(
+    reader: R,
+    tag: &str,
+    options: &ParseOptions,
+) -> AsyncRecordReader<T, R>
+where
+    T: XmlDeserialize,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    AsyncRecordReader {
+        reader,
+        tag: tag.to_string(),
+        parser: Parser::with_options(options),
+        format: options.format.clone(),
+        pending: std::collections::VecDeque::new(),
+        root: None,
+        finished: false,
+        _marker: PhantomData,
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub struct AsyncRecordReader<T, R> {
+    reader: R,
+    tag: String,
+    parser: Parser,
+    format: ValueFormat,
+    pending: std::collections::VecDeque<XmlElement>,
+    // 根节点本身就是记录时，收集其子节点直到根节点关闭
+    root: Option<XmlNodeRef>,
+    finished: bool,
+    _marker: PhantomData<T>,
+}
+
+#[cfg(feature = "tokio")]
+impl<T, R> AsyncRecordReader<T, R>
+where
+    T: XmlDeserialize,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    pub async fn next(&mut self) -> Option<Result<T, Error>> {
+        use tokio::io::AsyncReadExt;
+
+        let mut buf = [0u8; 8192];
+        loop {
+            if let Some(element) = self.pending.pop_front() {
+                return Some(format::with_format(self.format.clone(), || {
+                    T::deserialize(&element)
+                }));
+            }
+            if self.finished {
+                return None;
+            }
+
+            let len = match self.reader.read(&mut buf).await {
+                Ok(len) => len,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(Error::IoError(e)));
+                }
+            };
+            if len == 0 {
+                self.finished = true;
+                if !self.parser.is_closed() {
+                    return Some(Err(Error::BadXml));
+                }
+                continue;
+            }
+
+            let events = match self.parser.feed(&buf[..len]) {
+                Ok(events) => events,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            };
+            for event in events {
+                match (event, &self.root) {
+                    (StreamEvent::Start(XmlElement::Node(node)), _)
+                        if node.borrow().name.local_name == self.tag =>
+                    {
+                        self.root = Some(node);
+                    }
+                    (StreamEvent::Element(element), Some(root)) => {
+                        if let XmlElement::Node(node) = &element {
+                            node.borrow_mut().parent = Some(XmlNodeRef::downgrade(root));
+                        }
+                        root.borrow_mut().elements.push(element);
+                    }
+                    (StreamEvent::Element(element), None) => {
+                        find_records(element, &self.tag, &mut self.pending)
+                    }
+                    (StreamEvent::End, _) => {
+                        if let Some(root) = self.root.take() {
+                            self.pending.push_back(XmlElement::Node(root));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+// 按文档顺序查找名称为tag的节点，已匹配节点的子节点不再查找
+#[cfg(feature = "tokio")]
+fn find_records(
+    element: XmlElement,
+    tag: &str,
+    records: &mut std::collections::VecDeque<XmlElement>,
+) {
+    if let XmlElement::Node(node) = &element {
+        if node.borrow().name.local_name == tag {
+            node.borrow_mut().parent = None;
+            records.push_back(element);
+            return;
+        }
+        let children = node.borrow().elements.clone();
+        for child in children {
+            find_records(child, tag, records);
+        }
+    }
+}
+
+// 去掉首尾空白后用FromStr解析element的文本，保留解析错误信息
+pub fn parse_from_element<T>(element: &XmlElement) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut text = String::new();
+    element.text(&mut text);
+    let text = text.trim();
+    text.parse::<T>().map_err(|e| {
+        Error::Other(format!(
+            "\"{}\" can not convert to {}: {}",
+            text,
+            std::any::type_name::<T>(),
+            e
+        ))
+    })
+}
+
+// 将element的文本按分隔符拆分为多项，未指定分隔符时按空白拆分
+pub fn list_from_element<C>(element: &XmlElement, separator: Option<&str>) -> Result<C, Error>
+where
+    C: XmlCollection,
+    C::Item: XmlDeserialize,
+{
+    let mut text = String::new();
+    element.text(&mut text);
+
+    let items: Vec<&str> = match separator {
+        Some(separator) => {
+            if text.trim().is_empty() {
+                Vec::new()
+            } else {
+                text.split(separator).map(|item| item.trim()).collect()
+            }
+        }
+        None => text.split_whitespace().collect(),
+    };
+
+    let items = items
+        .into_iter()
+        .map(|item| C::Item::deserialize(&XmlElement::Text(item.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+    C::from_items(items)
+}
+
+// 将element的每个子节点反序列化为一项，指定名称时只取同名的子节点
+pub fn items_from_element<C>(element: &XmlElement, name: Option<&str>) -> Result<C, Error>
+where
+    C: XmlCollection,
+    C::Item: XmlDeserialize,
+{
+    let node = match element {
+        XmlElement::Node(node) => node.borrow(),
+        _ => return C::from_items(Vec::new()),
+    };
+    let items = node
+        .elements
+        .iter()
+        .filter(|element| match element {
+            XmlElement::Node(child) => match name {
+                Some(name) => child.borrow().name.local_name == name,
+                None => true,
+            },
+            _ => false,
+        })
+        .map(C::Item::deserialize)
+        .collect::<Result<Vec<_>, _>>()?;
+    C::from_items(items)
+}
+
 #[derive(Debug)]
 pub enum Error {
     XmlError(xml::reader::Error),
+    IoError(std::io::Error),
+    UnsupportedEncoding(String),
+    MalformedEncoding(String),
+    LimitExceeded(Limit),
     BadXml,
     Other(String),
 }
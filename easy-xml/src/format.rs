@@ -0,0 +1,244 @@
+use std::cell::RefCell;
+
+use crate::{de, XmlElement};
+
+// bool和数字的文本格式。全局格式由de::ParseOptions、se::SerializeSettings指定，
+// 在*_with_options调用期间保存在当前线程中；字段上的格式参数只覆盖指定的部分
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValueFormat {
+    pub bool_format: BoolFormat,
+    pub float_format: FloatFormat,
+    // 浮点数固定的小数位数
+    pub precision: Option<usize>,
+    // 整数写为十六进制，如0x1F，读取时同时接受十进制
+    pub hex: bool,
+    // 非负数前加+
+    pub plus_sign: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoolFormat {
+    // 只接受true/false
+    #[default]
+    Strict,
+    // 读取true/false/1/0，写为true/false
+    Xsd,
+    // 读取同Xsd，写为1/0
+    Numeric,
+    // 读取时还接受yes/no/y/n（不区分大小写），写为yes/no
+    YesNo,
+    // 读取同YesNo，写为Y/N
+    Yn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatFormat {
+    // 与Display一致，无穷大写为inf
+    #[default]
+    Display,
+    // 无穷大和非数写为INF、-INF、NaN
+    Xsd,
+}
+
+thread_local! {
+    static FORMAT: RefCell<ValueFormat> = RefCell::new(ValueFormat::default());
+}
+
+// 当前线程使用的格式
+pub fn current() -> ValueFormat {
+    FORMAT.with(|format| format.borrow().clone())
+}
+
+// 在f执行期间使用format，结束或panic后恢复原来的格式
+pub fn with_format<R>(format: ValueFormat, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<ValueFormat>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(format) = self.0.take() {
+                FORMAT.with(|current| *current.borrow_mut() = format);
+            }
+        }
+    }
+
+    let previous = FORMAT.with(|current| current.replace(format));
+    let _restore = Restore(Some(previous));
+    f()
+}
+
+// 按格式读写文本的类型，bool和数字类型的XmlDeserialize、XmlSerialize由此实现
+pub trait Lexical: Sized {
+    fn parse_lexical(text: &str, format: &ValueFormat) -> Result<Self, String>;
+
+    fn to_lexical(&self, format: &ValueFormat) -> String;
+}
+
+impl Lexical for bool {
+    fn parse_lexical(text: &str, format: &ValueFormat) -> Result<Self, String> {
+        let value = match (format.bool_format, text) {
+            (_, "true") => Some(true),
+            (_, "false") => Some(false),
+            (BoolFormat::Strict, _) => None,
+            (_, "1") => Some(true),
+            (_, "0") => Some(false),
+            (BoolFormat::YesNo | BoolFormat::Yn, text) => {
+                match text.to_ascii_lowercase().as_str() {
+                    "yes" | "y" => Some(true),
+                    "no" | "n" => Some(false),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        value.ok_or_else(|| format!("unknown boolean for {:?}", format.bool_format))
+    }
+
+    fn to_lexical(&self, format: &ValueFormat) -> String {
+        let (t, f) = match format.bool_format {
+            BoolFormat::Strict | BoolFormat::Xsd => ("true", "false"),
+            BoolFormat::Numeric => ("1", "0"),
+            BoolFormat::YesNo => ("yes", "no"),
+            BoolFormat::Yn => ("Y", "N"),
+        };
+        match self {
+            true => t.to_string(),
+            false => f.to_string(),
+        }
+    }
+}
+
+macro_rules! impl_lexical_for_int {
+    ($($x:ty),*) => {
+        $(
+            impl Lexical for $x {
+                fn parse_lexical(text: &str, format: &ValueFormat) -> Result<Self, String> {
+                    if format.hex {
+                        let (sign, digits) = split_sign(text);
+                        if let Some(digits) = digits
+                            .strip_prefix("0x")
+                            .or_else(|| digits.strip_prefix("0X"))
+                        {
+                            return <$x>::from_str_radix(&format!("{}{}", sign, digits), 16)
+                                .map_err(|e| e.to_string());
+                        }
+                    }
+                    text.parse::<$x>().map_err(|e| e.to_string())
+                }
+
+                fn to_lexical(&self, format: &ValueFormat) -> String {
+                    let value = *self as i128;
+                    let sign = match (value < 0, format.plus_sign) {
+                        (true, _) => "-",
+                        (false, true) => "+",
+                        (false, false) => "",
+                    };
+                    let magnitude = value.unsigned_abs();
+                    match format.hex {
+                        true => format!("{}0x{:X}", sign, magnitude),
+                        false => format!("{}{}", sign, magnitude),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_lexical_for_int!(usize, isize, u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+// u128超出i128的范围，单独处理
+impl Lexical for u128 {
+    fn parse_lexical(text: &str, format: &ValueFormat) -> Result<Self, String> {
+        if format.hex {
+            let (sign, digits) = split_sign(text);
+            if let Some(digits) = digits
+                .strip_prefix("0x")
+                .or_else(|| digits.strip_prefix("0X"))
+            {
+                return u128::from_str_radix(&format!("{}{}", sign, digits), 16)
+                    .map_err(|e| e.to_string());
+            }
+        }
+        text.parse::<u128>().map_err(|e| e.to_string())
+    }
+
+    fn to_lexical(&self, format: &ValueFormat) -> String {
+        let sign = match format.plus_sign {
+            true => "+",
+            false => "",
+        };
+        match format.hex {
+            true => format!("{}0x{:X}", sign, self),
+            false => format!("{}{}", sign, self),
+        }
+    }
+}
+
+fn split_sign(text: &str) -> (&str, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') | Some(b'+') => text.split_at(1),
+        _ => ("", text),
+    }
+}
+
+macro_rules! impl_lexical_for_float {
+    ($($x:ty),*) => {
+        $(
+            impl Lexical for $x {
+                fn parse_lexical(text: &str, _format: &ValueFormat) -> Result<Self, String> {
+                    // FromStr已接受INF、NaN及前导+
+                    text.parse::<$x>().map_err(|e| e.to_string())
+                }
+
+                fn to_lexical(&self, format: &ValueFormat) -> String {
+                    if format.float_format == FloatFormat::Xsd && !self.is_finite() {
+                        return match self.is_nan() {
+                            true => "NaN".to_string(),
+                            false if *self > 0.0 => "INF".to_string(),
+                            false => "-INF".to_string(),
+                        };
+                    }
+                    let text = match format.precision {
+                        Some(precision) if self.is_finite() => format!("{:.*}", precision, self),
+                        _ => self.to_string(),
+                    };
+                    match format.plus_sign && !self.is_sign_negative() && !self.is_nan() {
+                        true => format!("+{}", text),
+                        false => text,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_lexical_for_float!(f32, f64);
+
+// 去掉首尾空白后按当前格式解析element的文本
+pub fn parse_from_element<T: Lexical>(element: &XmlElement) -> Result<T, de::Error> {
+    let mut text = String::new();
+    element.text(&mut text);
+    let text = text.trim();
+    FORMAT
+        .with(|format| T::parse_lexical(text, &format.borrow()))
+        .map_err(|e| {
+            de::Error::Other(format!(
+                "\"{}\" can not convert to {}: {}",
+                text,
+                std::any::type_name::<T>(),
+                e
+            ))
+        })
+}
+
+// 按当前格式将值写为element的文本
+pub fn to_element<T: Lexical>(value: &T, element: &mut XmlElement) {
+    let text = FORMAT.with(|format| value.to_lexical(&format.borrow()));
+    match element {
+        XmlElement::Text(s) => s.push_str(text.as_str()),
+        XmlElement::Node(node) => node
+            .as_ref()
+            .borrow_mut()
+            .elements
+            .push(XmlElement::Text(text)),
+        _ => {}
+    }
+}
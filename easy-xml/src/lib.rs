@@ -5,11 +5,25 @@ pub type OwnedAttribute = xml::attribute::OwnedAttribute;
 pub type Namespace = xml::namespace::Namespace;
 pub type XmlVersion = xml::common::XmlVersion;
 
-use std::{
-    borrow::Borrow,
-    cell::RefCell,
-    rc::{Rc, Weak},
-};
+use std::rc::Rc;
+
+// 节点引用类型，开启 sync 特性后为线程安全的 Arc<SyncCell<XmlNode>>。
+// 注意sync特性不是叠加的：依赖图中任何一个crate开启后，所有crate中的节点类型都会改变，
+// 直接使用Rc<RefCell<XmlNode>>、Weak<RefCell<XmlNode>>的代码将无法编译，
+// 请始终使用XmlNodeRef、XmlNodeWeak及XmlNode::into_ref()
+#[cfg(not(feature = "sync"))]
+pub type XmlNodeCell = std::cell::RefCell<XmlNode>;
+#[cfg(not(feature = "sync"))]
+pub type XmlNodeRef = std::rc::Rc<XmlNodeCell>;
+#[cfg(not(feature = "sync"))]
+pub type XmlNodeWeak = std::rc::Weak<XmlNodeCell>;
+
+#[cfg(feature = "sync")]
+pub type XmlNodeCell = sync::SyncCell<XmlNode>;
+#[cfg(feature = "sync")]
+pub type XmlNodeRef = std::sync::Arc<XmlNodeCell>;
+#[cfg(feature = "sync")]
+pub type XmlNodeWeak = std::sync::Weak<XmlNodeCell>;
 
 #[derive(Debug, Clone)]
 pub struct XmlDocument {
@@ -22,10 +36,12 @@ pub struct XmlDocument {
 #[derive(Debug, Clone)]
 pub enum XmlElement {
     Text(String),
-    Node(Rc<RefCell<XmlNode>>),
+    Node(XmlNodeRef),
     Whitespace(String),
     Comment(String),
     CData(String),
+    // 原样写出的XML片段，由RawXml序列化时产生
+    Raw(String),
 }
 
 #[derive(Debug, Clone)]
@@ -34,27 +50,115 @@ pub struct XmlNode {
     pub attributes: Vec<OwnedAttribute>,
     pub namespace: Namespace,
     pub elements: Vec<XmlElement>,
-    pub parent: Option<Weak<RefCell<XmlNode>>>,
+    pub parent: Option<XmlNodeWeak>,
 }
 
 pub trait XmlDeserialize {
     fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
     where
         Self: Sized;
+
+    // 对应的节点或属性不存在时的值，None表示缺少时报错
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    // mixed字段中的节点能否由该类型读取，不能读取的节点被跳过
+    fn matches_mixed_node(_node: &XmlNode) -> bool
+    where
+        Self: Sized,
+    {
+        true
+    }
+
+    // 读取mixed字段中连续的文本和CDATA，返回None时忽略这段文本
+    fn deserialize_mixed_text(text: &str) -> Option<Result<Self, de::Error>>
+    where
+        Self: Sized,
+    {
+        Some(Self::deserialize(&XmlElement::Text(text.to_string())))
+    }
 }
 pub trait XmlSerialize {
     fn serialize(&self, element: &mut XmlElement)
     where
         Self: Sized;
+
+    // 为true时不写出对应的节点或属性
+    fn is_absent(&self) -> bool {
+        false
+    }
 }
 
+mod codec;
+mod collection;
 pub mod de;
+pub mod format;
+mod raw;
 pub mod se;
+#[cfg(feature = "sync")]
+pub mod sync;
+
+pub use collection::XmlCollection;
+pub use raw::RawXml;
 
 impl XmlNode {
     pub fn text(&self, string: &mut String) {
+        self.text_with(string, &TextOptions::default());
+    }
+
+    pub fn text_with(&self, string: &mut String, options: &TextOptions) {
+        let preserve = self.space_preserved();
+        if (options.trim || options.normalize_space) && !preserve {
+            let mut text = String::new();
+            self.collect_text(&mut text, options, preserve);
+            options.process(text.as_str(), string);
+        } else {
+            self.collect_text(string, options, preserve);
+        }
+    }
+
+    fn collect_text(&self, string: &mut String, options: &TextOptions, preserve: bool) {
         for e in &self.elements {
-            e.text(string);
+            match e {
+                XmlElement::Text(text) => string.push_str(text.as_str()),
+                XmlElement::Node(node) => {
+                    if options.deep {
+                        let node = node.as_ref().borrow();
+                        let preserve = node.space_preserved();
+                        node.collect_text(string, options, preserve);
+                    }
+                }
+                XmlElement::Whitespace(text) => {
+                    if preserve {
+                        string.push_str(text.as_str())
+                    }
+                }
+                XmlElement::Comment(_) | XmlElement::Raw(_) => {}
+                XmlElement::CData(cdata) => {
+                    if options.cdata {
+                        string.push_str(cdata.as_str())
+                    }
+                }
+            }
+        }
+    }
+
+    // 当前节点或最近的祖先节点是否声明了xml:space="preserve"
+    pub fn space_preserved(&self) -> bool {
+        if let Some(attr) = self
+            .attributes
+            .iter()
+            .find(|attr| attr.name.local_name == "space" && attr.name.prefix_ref() == Some("xml"))
+        {
+            return attr.value == "preserve";
+        }
+        match self.parent.as_ref().and_then(|parent| parent.upgrade()) {
+            Some(parent) => parent.as_ref().borrow().space_preserved(),
+            None => false,
         }
     }
 
@@ -71,18 +175,72 @@ impl XmlNode {
             parent: None,
         }
     }
+
+    pub fn into_ref(self) -> XmlNodeRef {
+        XmlNodeRef::new(XmlNodeCell::new(self))
+    }
+}
+// 文本提取选项
+#[derive(Debug, Clone, Copy)]
+pub struct TextOptions {
+    // 是否包含CDATA内容
+    pub cdata: bool,
+    // 是否包含子孙节点的文本，否则只取节点自身的文本
+    pub deep: bool,
+    // 去掉首尾空白
+    pub trim: bool,
+    // 去掉首尾空白，并将连续空白合并为一个空格
+    pub normalize_space: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            cdata: true,
+            deep: true,
+            trim: false,
+            normalize_space: false,
+        }
+    }
 }
+
+impl TextOptions {
+    fn process(&self, text: &str, string: &mut String) {
+        if self.normalize_space {
+            for (i, word) in text.split_whitespace().enumerate() {
+                if i > 0 {
+                    string.push(' ');
+                }
+                string.push_str(word);
+            }
+        } else if self.trim {
+            string.push_str(text.trim());
+        } else {
+            string.push_str(text);
+        }
+    }
+}
+
 impl XmlElement {
     pub fn text(&self, string: &mut String) {
+        self.text_with(string, &TextOptions::default());
+    }
+
+    pub fn text_with(&self, string: &mut String, options: &TextOptions) {
         match self {
-            XmlElement::Text(text) => string.push_str(text.as_str()),
+            XmlElement::Text(text) => options.process(text.as_str(), string),
             XmlElement::Node(node) => {
                 let node = node.as_ref().borrow();
-                node.text(string);
+                node.text_with(string, options);
             }
             XmlElement::Whitespace(_) => {}
             XmlElement::Comment(_) => {}
-            XmlElement::CData(_) => {}
+            XmlElement::Raw(_) => {}
+            XmlElement::CData(cdata) => {
+                if options.cdata {
+                    options.process(cdata.as_str(), string)
+                }
+            }
         }
     }
 }
@@ -100,6 +258,13 @@ where
             Err(_e) => Ok(None),
         }
     }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Some(None)
+    }
 }
 
 impl<T: XmlDeserialize> XmlDeserialize for Box<T>
@@ -115,6 +280,13 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(Box::new)
+    }
 }
 
 impl<T: XmlDeserialize> XmlDeserialize for std::rc::Rc<T>
@@ -130,6 +302,13 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(std::rc::Rc::new)
+    }
 }
 
 impl<T: XmlDeserialize> XmlDeserialize for std::sync::Arc<T>
@@ -145,6 +324,13 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(std::sync::Arc::new)
+    }
 }
 
 impl<T: XmlDeserialize> XmlDeserialize for std::cell::Cell<T>
@@ -160,6 +346,13 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(std::cell::Cell::new)
+    }
 }
 
 impl<T: XmlDeserialize> XmlDeserialize for std::cell::RefCell<T>
@@ -175,6 +368,57 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(std::cell::RefCell::new)
+    }
+}
+
+impl<T: XmlDeserialize> XmlDeserialize for std::sync::Mutex<T>
+where
+    T: Sized,
+{
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        match T::deserialize(element) {
+            Ok(obj) => Ok(std::sync::Mutex::new(obj)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(std::sync::Mutex::new)
+    }
+}
+
+impl<T: XmlDeserialize> XmlDeserialize for std::sync::RwLock<T>
+where
+    T: Sized,
+{
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        match T::deserialize(element) {
+            Ok(obj) => Ok(std::sync::RwLock::new(obj)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(std::sync::RwLock::new)
+    }
 }
 
 impl XmlDeserialize for String {
@@ -188,6 +432,91 @@ impl XmlDeserialize for String {
     }
 }
 
+impl<'a> XmlDeserialize for std::borrow::Cow<'a, str> {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(std::borrow::Cow::Owned(String::deserialize(element)?))
+    }
+}
+
+impl XmlDeserialize for Box<str> {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(String::deserialize(element)?.into_boxed_str())
+    }
+}
+
+impl XmlDeserialize for Rc<str> {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(Rc::from(String::deserialize(element)?))
+    }
+}
+
+impl XmlDeserialize for std::sync::Arc<str> {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(std::sync::Arc::from(String::deserialize(element)?))
+    }
+}
+
+// 文本只能是一个字符，多个字符时去掉首尾空白后再判断
+impl XmlDeserialize for char {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        let str = String::deserialize(element)?;
+        let mut chars = str.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(c);
+        }
+        let mut chars = str.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(de::Error::Other(format!(
+                "\"{}\" can not convert to char!",
+                str
+            ))),
+        }
+    }
+}
+
+// 为实现了FromStr和Display的类型实现XmlDeserialize和XmlSerialize，
+// 解析时去掉首尾空白，如impl_xml_scalar!(UserId, OrderId);
+#[macro_export]
+macro_rules! impl_xml_scalar {
+    ($($x:ty),* $(,)?) => {
+        $(
+            impl $crate::XmlDeserialize for $x {
+                fn deserialize(element: &$crate::XmlElement) -> Result<Self, $crate::de::Error>
+                where
+                    Self: Sized,
+                {
+                    $crate::de::parse_from_element(element)
+                }
+            }
+
+            impl $crate::XmlSerialize for $x {
+                fn serialize(&self, element: &mut $crate::XmlElement)
+                where
+                    Self: Sized,
+                {
+                    $crate::se::display_to_element(self, element);
+                }
+            }
+        )*
+    };
+}
+
 macro_rules! impl_de_for_number {
     ($x:ty) => {
         impl XmlDeserialize for $x {
@@ -195,16 +524,7 @@ macro_rules! impl_de_for_number {
             where
                 Self: Sized,
             {
-                let str = String::deserialize(element)?;
-                let str = str.trim();
-                match str.parse::<$x>() {
-                    Ok(val) => Ok(val),
-                    Err(_) => {
-                        let msg = format!("\"{}\" can not convert to number!", str);
-
-                        Err(de::Error::Other(msg))
-                    }
-                }
+                format::parse_from_element(element)
             }
         }
     };
@@ -263,6 +583,10 @@ impl<T: XmlSerialize> XmlSerialize for Option<T> {
             None => {}
         }
     }
+
+    fn is_absent(&self) -> bool {
+        self.is_none()
+    }
 }
 
 impl<T: XmlSerialize> XmlSerialize for Box<T> {
@@ -272,6 +596,10 @@ impl<T: XmlSerialize> XmlSerialize for Box<T> {
     {
         self.as_ref().serialize(element);
     }
+
+    fn is_absent(&self) -> bool {
+        self.as_ref().is_absent()
+    }
 }
 impl<T: XmlSerialize> XmlSerialize for Rc<T> {
     fn serialize(&self, element: &mut XmlElement)
@@ -280,6 +608,10 @@ impl<T: XmlSerialize> XmlSerialize for Rc<T> {
     {
         self.as_ref().serialize(element);
     }
+
+    fn is_absent(&self) -> bool {
+        self.as_ref().is_absent()
+    }
 }
 impl<T: XmlSerialize> XmlSerialize for std::sync::Arc<T> {
     fn serialize(&self, element: &mut XmlElement)
@@ -288,14 +620,22 @@ impl<T: XmlSerialize> XmlSerialize for std::sync::Arc<T> {
     {
         self.as_ref().serialize(element);
     }
+
+    fn is_absent(&self) -> bool {
+        self.as_ref().is_absent()
+    }
 }
 
-impl<T: XmlSerialize> XmlSerialize for std::cell::Cell<T> {
+impl<T: XmlSerialize + Copy> XmlSerialize for std::cell::Cell<T> {
     fn serialize(&self, element: &mut XmlElement)
     where
         Self: Sized,
     {
-        self.borrow().serialize(element);
+        self.get().serialize(element);
+    }
+
+    fn is_absent(&self) -> bool {
+        self.get().is_absent()
     }
 }
 
@@ -306,6 +646,100 @@ impl<T: XmlSerialize> XmlSerialize for std::cell::RefCell<T> {
     {
         self.borrow().serialize(element);
     }
+
+    fn is_absent(&self) -> bool {
+        self.borrow().is_absent()
+    }
+}
+
+impl<T: XmlSerialize> XmlSerialize for std::sync::Mutex<T> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        match self.lock() {
+            Ok(guard) => guard.serialize(element),
+            Err(poisoned) => poisoned.into_inner().serialize(element),
+        }
+    }
+
+    fn is_absent(&self) -> bool {
+        match self.lock() {
+            Ok(guard) => guard.is_absent(),
+            Err(poisoned) => poisoned.into_inner().is_absent(),
+        }
+    }
+}
+
+impl<T: XmlSerialize> XmlSerialize for std::sync::RwLock<T> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        match self.read() {
+            Ok(guard) => guard.serialize(element),
+            Err(poisoned) => poisoned.into_inner().serialize(element),
+        }
+    }
+
+    fn is_absent(&self) -> bool {
+        match self.read() {
+            Ok(guard) => guard.is_absent(),
+            Err(poisoned) => poisoned.into_inner().is_absent(),
+        }
+    }
+}
+
+// 字符串切片类型的序列化
+fn serialize_str(value: &str, element: &mut XmlElement) {
+    match element {
+        XmlElement::Text(text) => {
+            text.push_str(value);
+        }
+        XmlElement::Node(node) => {
+            node.as_ref()
+                .borrow_mut()
+                .elements
+                .push(XmlElement::Text(value.to_string()));
+        }
+        _ => {}
+    }
+}
+
+impl<'a> XmlSerialize for std::borrow::Cow<'a, str> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        serialize_str(self, element);
+    }
+}
+
+impl XmlSerialize for Box<str> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        serialize_str(self, element);
+    }
+}
+
+impl XmlSerialize for Rc<str> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        serialize_str(self, element);
+    }
+}
+
+impl XmlSerialize for std::sync::Arc<str> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        serialize_str(self, element);
+    }
 }
 
 macro_rules! impl_se_for_number {
@@ -315,18 +749,7 @@ macro_rules! impl_se_for_number {
             where
                 Self: Sized,
             {
-                match element {
-                    XmlElement::Text(text) => {
-                        text.push_str(self.to_string().as_str());
-                    }
-                    XmlElement::Node(node) => {
-                        node.as_ref()
-                            .borrow_mut()
-                            .elements
-                            .push(XmlElement::Text(self.to_string()));
-                    }
-                    _ => {}
-                }
+                format::to_element(self, element);
             }
         }
     };
@@ -350,3 +773,52 @@ impl_se_for_number!(i128);
 impl_se_for_number!(f32);
 impl_se_for_number!(f64);
 impl_se_for_number!(bool);
+
+impl XmlSerialize for char {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        se::display_to_element(self, element);
+    }
+}
+
+impl_xml_scalar!(
+    std::num::NonZeroUsize,
+    std::num::NonZeroU8,
+    std::num::NonZeroU16,
+    std::num::NonZeroU32,
+    std::num::NonZeroU64,
+    std::num::NonZeroU128,
+    std::num::NonZeroIsize,
+    std::num::NonZeroI8,
+    std::num::NonZeroI16,
+    std::num::NonZeroI32,
+    std::num::NonZeroI64,
+    std::num::NonZeroI128,
+    std::net::IpAddr,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+    std::net::SocketAddr,
+    std::net::SocketAddrV4,
+    std::net::SocketAddrV6,
+);
+
+// PathBuf没有实现Display，按文本读写
+impl XmlDeserialize for std::path::PathBuf {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(std::path::PathBuf::from(String::deserialize(element)?))
+    }
+}
+
+impl XmlSerialize for std::path::PathBuf {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        serialize_str(self.to_string_lossy().as_ref(), element);
+    }
+}
@@ -0,0 +1,313 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+    rc::Rc,
+};
+
+use xml::namespace::{NS_EMPTY_URI, NS_NO_PREFIX, NS_XMLNS_PREFIX, NS_XML_PREFIX};
+
+use crate::{
+    de, Namespace, OwnedName, XmlDeserialize, XmlElement, XmlNode, XmlNodeRef, XmlSerialize,
+};
+
+// 原样保存节点内部的XML文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawXml {
+    pub xml: String,
+    // 节点上有效的命名空间（不含xml、xmlns），序列化时声明在外层节点上，
+    // 使片段中用到的前缀都有定义
+    pub namespace: Namespace,
+}
+
+impl RawXml {
+    pub fn new(xml: impl Into<String>) -> Self {
+        RawXml {
+            xml: xml.into(),
+            namespace: Namespace::empty(),
+        }
+    }
+}
+
+impl Default for RawXml {
+    fn default() -> Self {
+        RawXml::new(String::new())
+    }
+}
+
+// 去掉xml、xmlns及空的默认命名空间
+fn declared_namespace(namespace: &Namespace) -> Namespace {
+    let mut declared = Namespace::empty();
+    for (prefix, uri) in namespace {
+        match (prefix, uri) {
+            (NS_XML_PREFIX, _) | (NS_XMLNS_PREFIX, _) | (NS_NO_PREFIX, NS_EMPTY_URI) => {}
+            (prefix, uri) => {
+                declared.put(prefix, uri);
+            }
+        }
+    }
+    declared
+}
+
+// 源文本及每个节点内部（开始标签之后到结束标签之前）在其中的范围，
+// 只在反序列化期间保存在当前线程中，节点本身不持有源文本
+pub(crate) struct SourceMap {
+    source: String,
+    // 以节点的地址为键
+    spans: HashMap<usize, Range<usize>>,
+}
+
+impl SourceMap {
+    pub(crate) fn new(source: String, spans: HashMap<usize, Range<usize>>) -> Self {
+        SourceMap { source, spans }
+    }
+}
+
+thread_local! {
+    static SOURCE: RefCell<Option<Rc<SourceMap>>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn node_key(node: &XmlNodeRef) -> usize {
+    XmlNodeRef::as_ptr(node) as usize
+}
+
+// 在f执行期间使用source，结束或panic后恢复
+pub(crate) fn with_source<R>(source: Option<SourceMap>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Rc<SourceMap>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SOURCE.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    let previous = SOURCE.with(|current| current.replace(source.map(Rc::new)));
+    let _restore = Restore(previous);
+    f()
+}
+
+// 节点内部的源文本
+fn inner_xml(node: &XmlNodeRef) -> Option<String> {
+    SOURCE.with(|source| {
+        let source = source.borrow();
+        let source = source.as_ref()?;
+        let span = source.spans.get(&node_key(node))?;
+        Some(source.source[span.clone()].to_string())
+    })
+}
+
+impl XmlDeserialize for XmlElement {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(element.clone())
+    }
+}
+
+impl XmlSerialize for XmlElement {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        if let XmlElement::Node(source) = self {
+            source.borrow().serialize(element);
+            return;
+        }
+        match element {
+            XmlElement::Node(node) => node.borrow_mut().elements.push(self.clone()),
+            XmlElement::Text(text) => self.text(text),
+            _ => {}
+        }
+    }
+}
+
+impl XmlDeserialize for XmlNode {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        match element {
+            XmlElement::Node(node) => Ok(node.borrow().clone()),
+            _ => Err(de::Error::Other("Expected a node!".to_string())),
+        }
+    }
+}
+
+impl XmlSerialize for XmlNode {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        match element {
+            XmlElement::Node(node) => {
+                let mut node = node.borrow_mut();
+                // 节点名称优先使用字段上指定的名称
+                if node.name.local_name.is_empty() {
+                    node.name = self.name.clone();
+                }
+                node.attributes.extend(self.attributes.iter().cloned());
+                for (prefix, uri) in &self.namespace {
+                    node.namespace.put(prefix, uri);
+                }
+                node.elements.extend(self.elements.iter().cloned());
+            }
+            XmlElement::Text(text) => self.text(text),
+            _ => {}
+        }
+    }
+}
+
+impl XmlDeserialize for RawXml {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        let mut xml = String::new();
+        match element {
+            XmlElement::Node(node) => {
+                let namespace = declared_namespace(&node.borrow().namespace);
+                // 保留了源文本时直接取源文本
+                if let Some(inner) = inner_xml(node) {
+                    return Ok(RawXml {
+                        xml: inner,
+                        namespace,
+                    });
+                }
+                let node = node.borrow();
+                for e in &node.elements {
+                    write_element(e, &node.namespace, &BTreeMap::new(), &mut xml);
+                }
+                Ok(RawXml { xml, namespace })
+            }
+            _ => {
+                write_element(element, &Namespace::empty(), &BTreeMap::new(), &mut xml);
+                Ok(RawXml::new(xml))
+            }
+        }
+    }
+}
+
+impl XmlSerialize for RawXml {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        // 原样写出，不检查是否为合法的XML
+        match element {
+            XmlElement::Node(node) => {
+                let mut node = node.borrow_mut();
+                // 外层节点已有的声明不覆盖
+                for (prefix, uri) in &self.namespace {
+                    node.namespace.put(prefix, uri);
+                }
+                node.elements.push(XmlElement::Raw(self.xml.clone()));
+            }
+            XmlElement::Text(text) => text.push_str(self.xml.as_str()),
+            _ => {}
+        }
+    }
+}
+
+// 写出元素。命名空间声明包括节点自身声明的，以及节点或属性用到但尚未声明的，
+// 使得片段可以单独解析
+fn write_element(
+    element: &XmlElement,
+    parent: &Namespace,
+    declared: &BTreeMap<String, String>,
+    xml: &mut String,
+) {
+    match element {
+        XmlElement::Text(text) | XmlElement::Whitespace(text) => escape(text, false, xml),
+        XmlElement::CData(cdata) => {
+            xml.push_str("<![CDATA[");
+            xml.push_str(cdata.replace("]]>", "]]]]><![CDATA[>").as_str());
+            xml.push_str("]]>");
+        }
+        XmlElement::Comment(comment) => {
+            xml.push_str("<!--");
+            xml.push_str(comment);
+            xml.push_str("-->");
+        }
+        XmlElement::Raw(raw) => xml.push_str(raw),
+        XmlElement::Node(node) => {
+            let node = node.borrow();
+            let name = qualified_name(&node.name);
+
+            let mut declared = declared.clone();
+            let mut declarations = BTreeMap::new();
+            for (prefix, uri) in &node.namespace {
+                if parent.get(prefix) != Some(uri) && declared.get(prefix) != Some(&uri.to_string())
+                {
+                    declarations.insert(prefix.to_string(), uri.to_string());
+                }
+            }
+            let used = std::iter::once(&node.name).chain(node.attributes.iter().map(|a| &a.name));
+            for used in used {
+                if let Some(uri) = &used.namespace {
+                    let prefix = used.prefix.clone().unwrap_or_default();
+                    if declared.get(&prefix) != Some(uri) {
+                        declarations.insert(prefix, uri.clone());
+                    }
+                }
+            }
+
+            xml.push('<');
+            xml.push_str(name.as_str());
+            for (prefix, uri) in declarations {
+                if prefix == "xml" || prefix == "xmlns" {
+                    continue;
+                }
+                if prefix.is_empty() {
+                    xml.push_str(" xmlns=\"");
+                } else {
+                    xml.push_str(" xmlns:");
+                    xml.push_str(prefix.as_str());
+                    xml.push_str("=\"");
+                }
+                escape(uri.as_str(), true, xml);
+                xml.push('"');
+                declared.insert(prefix, uri);
+            }
+            for attr in &node.attributes {
+                xml.push(' ');
+                xml.push_str(qualified_name(&attr.name).as_str());
+                xml.push_str("=\"");
+                escape(attr.value.as_str(), true, xml);
+                xml.push('"');
+            }
+            if node.elements.is_empty() {
+                xml.push_str("/>");
+                return;
+            }
+            xml.push('>');
+            for e in &node.elements {
+                write_element(e, &node.namespace, &declared, xml);
+            }
+            xml.push_str("</");
+            xml.push_str(name.as_str());
+            xml.push('>');
+        }
+    }
+}
+
+fn qualified_name(name: &OwnedName) -> String {
+    match name.prefix_ref() {
+        Some(prefix) => format!("{}:{}", prefix, name.local_name),
+        None => name.local_name.clone(),
+    }
+}
+
+fn escape(text: &str, attribute: bool, xml: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' if attribute => xml.push_str("&quot;"),
+            '\n' if attribute => xml.push_str("&#xA;"),
+            '\t' if attribute => xml.push_str("&#x9;"),
+            _ => xml.push(c),
+        }
+    }
+}
@@ -1,17 +1,26 @@
-use std::{borrow::Cow, cell::RefCell, io::Write, rc::Rc, string::FromUtf8Error};
+use std::{borrow::Cow, fmt::Display, io::Write, string::FromUtf8Error};
 
 use xml::{
     attribute::Attribute,
+    escape::{escape_str_attribute, escape_str_pcdata},
     name::{Name, OwnedName},
     writer::XmlEvent,
     EmitterConfig, EventWriter,
 };
 
-use crate::{XmlDocument, XmlElement, XmlNode, XmlSerialize};
+use crate::{
+    codec::Codec,
+    format::{self, ValueFormat},
+    Namespace, XmlCollection, XmlDocument, XmlElement, XmlNode, XmlSerialize,
+};
 
 pub struct SerializeSettings {
+    // pretty_format时每层缩进的空格数
     pub indent: u32,
+    // 子节点换行并缩进
     pub pretty_format: bool,
+    // bool和数字的文本格式
+    pub format: ValueFormat,
 }
 
 impl Default for SerializeSettings {
@@ -19,6 +28,7 @@ impl Default for SerializeSettings {
         Self {
             indent: 4,
             pretty_format: false,
+            format: ValueFormat::default(),
         }
     }
 }
@@ -37,22 +47,41 @@ fn owned_name_to_name(owned_name: &OwnedName) -> Name {
     }
 }
 
+// 文本和属性值中编码无法表示的字符写成数字字符引用（&#NNNN;），CDATA在这些字符处拆开，
+// 注释、名称及原样写出的片段中无法表示时返回错误
 fn format_xml_element<W: Write>(
     w: &mut EventWriter<W>,
     element: &XmlElement,
-) -> xml::writer::Result<()> {
+    codec: Codec,
+) -> Result<(), Error> {
     match element {
         XmlElement::Text(text) => {
-            w.write(XmlEvent::characters(text.as_str()))?;
+            let text = char_refs(escape_str_pcdata(text), codec);
+            w.write(XmlEvent::characters(&text))
+                .map_err(Error::EmitterError)?;
         }
         XmlElement::Node(node) => {
             let node = &*node.borrow_mut();
-            let attributes = &node.attributes;
-            let attributes = attributes
-                .into_iter()
-                .map(|attr| Attribute {
+            check_name(&node.name, codec)?;
+            for (prefix, uri) in &node.namespace {
+                check_encodable(prefix, codec)?;
+                check_encodable(uri, codec)?;
+            }
+            let values = node
+                .attributes
+                .iter()
+                .map(|attr| {
+                    check_name(&attr.name, codec)?;
+                    Ok(char_refs(escape_str_attribute(&attr.value), codec))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let attributes = node
+                .attributes
+                .iter()
+                .zip(values.iter())
+                .map(|(attr, value)| Attribute {
                     name: owned_name_to_name(&attr.name),
-                    value: attr.value.as_str(),
+                    value: value.as_ref(),
                 })
                 .collect::<Vec<_>>();
 
@@ -60,36 +89,132 @@ fn format_xml_element<W: Write>(
                 name: owned_name_to_name(&node.name),
                 attributes: Cow::Borrowed(attributes.as_slice()),
                 namespace: Cow::Borrowed(&node.namespace),
-            })?;
+            })
+            .map_err(Error::EmitterError)?;
 
             let elements = &node.elements;
             for e in elements {
-                format_xml_element(w, e)?;
+                format_xml_element(w, e, codec)?;
             }
 
             w.write(XmlEvent::EndElement {
                 name: Some(owned_name_to_name(&node.name)),
-            })?;
+            })
+            .map_err(Error::EmitterError)?;
         }
         XmlElement::Whitespace(_) => {}
+        XmlElement::Raw(raw) => {
+            // 片段中的字符引用无法区分上下文，不做替换
+            check_encodable(raw, codec)?;
+            w.write(XmlEvent::characters(raw))
+                .map_err(Error::EmitterError)?;
+        }
         XmlElement::Comment(comment) => {
-            w.write(XmlEvent::Comment(comment))?;
+            // 注释中不能出现"--"，也不能以"-"结尾
+            let mut comment = comment.replace("--", "- -");
+            while comment.contains("--") {
+                comment = comment.replace("--", "- -");
+            }
+            if comment.ends_with('-') {
+                comment.push(' ');
+            }
+            check_encodable(&comment, codec)?;
+            w.write(XmlEvent::Comment(comment.as_str()))
+                .map_err(Error::EmitterError)?;
         }
         XmlElement::CData(cdata) => {
-            w.write(XmlEvent::CData(cdata))?;
+            // "]]>"会提前结束CDATA，拆分到两个CDATA中
+            let mut rest = cdata.as_str();
+            while let Some(index) = rest.find("]]>") {
+                write_cdata(w, &rest[..index + 2], codec)?;
+                rest = &rest[index + 2..];
+            }
+            write_cdata(w, rest, codec)?;
         }
     }
     Ok(())
 }
-fn format_xml<W: Write>(w: &mut EventWriter<W>, doc: &XmlDocument) -> xml::writer::Result<()> {
+
+// CDATA中不能使用字符引用，无法表示的字符写在CDATA之外
+fn write_cdata<W: Write>(w: &mut EventWriter<W>, cdata: &str, codec: Codec) -> Result<(), Error> {
+    let mut start = 0;
+    for (i, c) in cdata.char_indices() {
+        if codec.can_encode(c) {
+            continue;
+        }
+        if start < i {
+            w.write(XmlEvent::CData(&cdata[start..i]))
+                .map_err(Error::EmitterError)?;
+        }
+        w.write(XmlEvent::characters(&format!("&#{};", c as u32)))
+            .map_err(Error::EmitterError)?;
+        start = i + c.len_utf8();
+    }
+    if start == 0 || start < cdata.len() {
+        w.write(XmlEvent::CData(&cdata[start..]))
+            .map_err(Error::EmitterError)?;
+    }
+    Ok(())
+}
+
+// 已转义的文本中，将无法表示的字符替换成数字字符引用
+fn char_refs(text: Cow<str>, codec: Codec) -> Cow<str> {
+    if text.chars().all(|c| codec.can_encode(c)) {
+        return text;
+    }
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if codec.can_encode(c) {
+            result.push(c);
+        } else {
+            result.push_str(&format!("&#{};", c as u32));
+        }
+    }
+    Cow::Owned(result)
+}
+
+fn check_name(name: &OwnedName, codec: Codec) -> Result<(), Error> {
+    if name.local_name.is_empty() {
+        return Err(Error::UnnamedNode);
+    }
+    check_encodable(&name.local_name, codec)?;
+    if let Some(prefix) = &name.prefix {
+        check_encodable(prefix, codec)?;
+    }
+    Ok(())
+}
+
+fn check_encodable(text: &str, codec: Codec) -> Result<(), Error> {
+    match text.chars().find(|c| !codec.can_encode(*c)) {
+        Some(c) => Err(Error::UnencodableCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+// 转义由format_xml_element按编码处理
+fn emitter_config(settings: &SerializeSettings) -> EmitterConfig {
+    let mut config = EmitterConfig::new()
+        .autopad_comments(false)
+        .perform_indent(settings.pretty_format)
+        .indent_string(" ".repeat(settings.indent as usize));
+    config.perform_escaping = false;
+    config
+}
+
+fn format_xml<W: Write>(
+    w: &mut EventWriter<W>,
+    doc: &XmlDocument,
+    codec: Codec,
+) -> Result<(), Error> {
     w.write(XmlEvent::StartDocument {
         version: doc.version,
         encoding: Some(doc.encoding.as_str()),
         standalone: doc.standalone.clone(),
-    })?;
+    })
+    .map_err(Error::EmitterError)?;
 
     for e in &doc.elements {
-        format_xml_element(w, e)?;
+        format_xml_element(w, e, codec)?;
     }
 
     Ok(())
@@ -99,30 +224,83 @@ fn format_xml<W: Write>(w: &mut EventWriter<W>, doc: &XmlDocument) -> xml::write
 pub enum Error {
     EmitterError(xml::writer::Error),
     FromUtf8Error(FromUtf8Error),
+    UnsupportedEncoding(String),
+    // 注释或名称中有编码无法表示的字符
+    UnencodableCharacter(char),
+    // 节点没有名称，如嵌套集合的内层项既没有item参数，类型本身也不设置名称
+    UnnamedNode,
 }
 
 pub fn to_string<T: XmlSerialize>(t: &T) -> Result<String, Error> {
-    match to_bytes(t, "UTF-8") {
+    to_string_with_options(t, &SerializeSettings::default())
+}
+
+pub fn to_string_with_options<T: XmlSerialize>(
+    t: &T,
+    settings: &SerializeSettings,
+) -> Result<String, Error> {
+    match to_bytes_with_options(t, "UTF-8", settings) {
         Ok(v8) => match String::from_utf8(v8) {
             Ok(s) => return Ok(s),
             Err(e) => return Err(Error::FromUtf8Error(e)),
         },
-        Err(e) => return Err(Error::EmitterError(e)),
+        Err(e) => return Err(e),
     }
 }
 
-pub fn to_bytes<T: XmlSerialize>(t: &T, encoding: &str) -> xml::writer::Result<Vec<u8>> {
+// 按encoding编码输出，无法表示的字符写成数字字符引用（&#NNNN;）
+pub fn to_bytes<T: XmlSerialize>(t: &T, encoding: &str) -> Result<Vec<u8>, Error> {
+    to_bytes_with_options(t, encoding, &SerializeSettings::default())
+}
+
+pub fn to_bytes_with_options<T: XmlSerialize>(
+    t: &T,
+    encoding: &str,
+    settings: &SerializeSettings,
+) -> Result<Vec<u8>, Error> {
+    let codec = match Codec::for_label(encoding) {
+        Some(codec) => codec,
+        None => return Err(Error::UnsupportedEncoding(encoding.to_string())),
+    };
+
     let mut v8: Vec<u8> = Vec::new();
-    let mut writer = EmitterConfig::new().create_writer(&mut v8);
-    serialize(t, &mut writer, encoding)?;
-    return Ok(v8);
+    let mut writer = emitter_config(settings).create_writer(&mut v8);
+    format::with_format(settings.format.clone(), || {
+        serialize(t, &mut writer, encoding, codec)
+    })?;
+    if codec == Codec::UTF_8 {
+        return Ok(v8);
+    }
+    // 输出的都是合法的UTF-8，且只含编码能表示的字符
+    let text = String::from_utf8_lossy(&v8);
+    Ok(codec.encode(&text))
+}
+
+#[cfg(feature = "tokio")]
+pub async fn to_async_writer<T, W>(t: &T, writer: &mut W) -> Result<(), Error>
+where
+    T: XmlSerialize,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let bytes = to_bytes(t, "UTF-8")?;
+    match writer.write_all(&bytes).await {
+        Ok(_) => {}
+        Err(e) => return Err(Error::EmitterError(e.into())),
+    }
+    match writer.flush().await {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::EmitterError(e.into())),
+    }
 }
 
 fn serialize<T: XmlSerialize, W: Write>(
     t: &T,
     writer: &mut EventWriter<W>,
     encoding: &str,
-) -> xml::writer::Result<()> {
+    codec: Codec,
+) -> Result<(), Error> {
     let mut doc = XmlDocument {
         version: xml::common::XmlVersion::Version10,
         encoding: encoding.to_string(),
@@ -130,10 +308,127 @@ fn serialize<T: XmlSerialize, W: Write>(
         elements: Vec::new(),
     };
 
-    let mut root = XmlElement::Node(Rc::new(RefCell::new(XmlNode::empty())));
+    let mut root = XmlElement::Node(XmlNode::empty().into_ref());
     t.serialize(&mut root);
 
     doc.elements.push(root);
 
-    format_xml(writer, &doc)
+    format_xml(writer, &doc, codec)
+}
+
+// 用Display将值写为element的文本
+pub fn display_to_element<T: Display + ?Sized>(value: &T, element: &mut XmlElement) {
+    match element {
+        XmlElement::Text(text) => {
+            text.push_str(value.to_string().as_str());
+        }
+        XmlElement::Node(node) => {
+            node.as_ref()
+                .borrow_mut()
+                .elements
+                .push(XmlElement::Text(value.to_string()));
+        }
+        _ => {}
+    }
+}
+
+// 将每一项序列化为文本，用分隔符连接后写入element
+pub fn list_to_element<C>(collection: &C, separator: &str, element: &mut XmlElement)
+where
+    C: XmlCollection,
+    C::Item: XmlSerialize,
+{
+    let mut list = String::new();
+    for (i, item) in collection.items().enumerate() {
+        if i > 0 {
+            list.push_str(separator);
+        }
+        let mut text = XmlElement::Text(String::new());
+        item.serialize(&mut text);
+        text.text(&mut list);
+    }
+    list.serialize(element);
+}
+
+// 每一项写为element的一个子节点，未指定名称时由项自己设置节点名称
+pub fn items_to_element<C>(collection: &C, name: Option<&str>, element: &mut XmlElement)
+where
+    C: XmlCollection,
+    C::Item: XmlSerialize,
+{
+    let node = match element {
+        XmlElement::Node(node) => node.clone(),
+        _ => {
+            for item in collection.items() {
+                item.serialize(element);
+            }
+            return;
+        }
+    };
+    for item in collection.items() {
+        let mut child = XmlNode::empty();
+        if let Some(name) = name {
+            child.name.local_name = name.to_string();
+        }
+        let child = child.into_ref();
+        item.serialize(&mut XmlElement::Node(child.clone()));
+        // 没有名称的项保留为无名节点，写出时返回Error::UnnamedNode，不合并到element中
+        node.borrow_mut().elements.push(XmlElement::Node(child));
+    }
+}
+
+// 增量写入：先写声明和根节点开始标签，逐条写入记录，最后关闭根节点
+pub struct StreamWriter<W: Write> {
+    writer: EventWriter<W>,
+    root: OwnedName,
+}
+
+impl<W: Write> StreamWriter<W> {
+    pub fn new(sink: W, root: &str) -> Result<Self, Error> {
+        let mut writer = emitter_config(&SerializeSettings::default()).create_writer(sink);
+        let root = OwnedName::local(root);
+
+        let result = writer
+            .write(XmlEvent::StartDocument {
+                version: xml::common::XmlVersion::Version10,
+                encoding: Some("UTF-8"),
+                standalone: None,
+            })
+            .and_then(|_| {
+                writer.write(XmlEvent::StartElement {
+                    name: root.borrow(),
+                    attributes: Cow::Borrowed(&[]),
+                    namespace: Cow::Owned(Namespace::empty()),
+                })
+            });
+        match result {
+            Ok(_) => Ok(StreamWriter { writer, root }),
+            Err(e) => Err(Error::EmitterError(e)),
+        }
+    }
+
+    // 只构建当前记录的节点树，写入后立即flush
+    pub fn write_item<T: XmlSerialize>(&mut self, t: &T) -> Result<(), Error> {
+        let mut item = XmlElement::Node(XmlNode::empty().into_ref());
+        t.serialize(&mut item);
+
+        format_xml_element(&mut self.writer, &item, Codec::UTF_8)?;
+        match self.writer.inner_mut().flush() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::EmitterError(e.into())),
+        }
+    }
+
+    pub fn finish(mut self) -> Result<W, Error> {
+        if let Err(e) = self.writer.write(XmlEvent::EndElement {
+            name: Some(self.root.borrow()),
+        }) {
+            return Err(Error::EmitterError(e));
+        }
+        let mut sink = self.writer.into_inner();
+        match sink.flush() {
+            Ok(_) => Ok(sink),
+            Err(e) => Err(Error::EmitterError(e.into())),
+        }
+    }
 }
@@ -0,0 +1,70 @@
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{de, XmlDeserialize, XmlElement, XmlSerialize};
+
+// 线程安全的节点容器，提供与RefCell一致的borrow/borrow_mut接口，
+// 使派生代码在是否开启sync特性时保持一致
+#[derive(Debug, Default)]
+pub struct SyncCell<T>(RwLock<T>);
+
+impl<T> SyncCell<T> {
+    pub fn new(value: T) -> Self {
+        SyncCell(RwLock::new(value))
+    }
+
+    pub fn borrow(&self) -> RwLockReadGuard<'_, T> {
+        match self.0.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    pub fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+        match self.0.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        match self.0.into_inner() {
+            Ok(value) => value,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl<T: Clone> Clone for SyncCell<T> {
+    fn clone(&self) -> Self {
+        SyncCell::new(self.borrow().clone())
+    }
+}
+
+impl<T: XmlDeserialize> XmlDeserialize for SyncCell<T> {
+    fn deserialize(element: &XmlElement) -> Result<Self, de::Error>
+    where
+        Self: Sized,
+    {
+        Ok(SyncCell::new(T::deserialize(element)?))
+    }
+
+    fn absent() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        T::absent().map(SyncCell::new)
+    }
+}
+
+impl<T: XmlSerialize> XmlSerialize for SyncCell<T> {
+    fn serialize(&self, element: &mut XmlElement)
+    where
+        Self: Sized,
+    {
+        self.borrow().serialize(element);
+    }
+
+    fn is_absent(&self) -> bool {
+        self.borrow().is_absent()
+    }
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "tokio")]
+
+use easy_xml::{de, se};
+
+#[macro_use]
+extern crate easy_xml_derive;
+
+#[derive(PartialEq, Debug, XmlDeserialize, XmlSerialize)]
+struct Record {
+    #[easy_xml(attribute)]
+    id: u32,
+    #[easy_xml(rename = "Name")]
+    name: String,
+}
+
+#[tokio::test]
+async fn test_from_async_reader() {
+    let xml = r#"<Record id="1"><Name>a</Name></Record>"#;
+    let record: Record = de::from_async_reader(xml.as_bytes()).await.unwrap();
+    assert_eq!(
+        record,
+        Record {
+            id: 1,
+            name: "a".to_string()
+        }
+    );
+
+    let mut bytes = Vec::new();
+    se::to_async_writer(&record, &mut bytes).await.unwrap();
+    assert_eq!(
+        String::from_utf8(bytes).unwrap().as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Record id="1"><Name>a</Name></Record>"#
+    );
+}
+
+#[tokio::test]
+async fn test_iter_from_async_reader() {
+    let mut xml = String::from("<Dump><Meta/>");
+    for id in 0..1000 {
+        xml.push_str(&format!(
+            r#"<Record id="{}"><Name>r{}</Name></Record>"#,
+            id, id
+        ));
+    }
+    xml.push_str("</Dump>");
+
+    let mut records = de::iter_from_async_reader::<Record, _>(xml.as_bytes(), "Record");
+    let mut id = 0;
+    while let Some(record) = records.next().await {
+        let record = record.unwrap();
+        assert_eq!(record.id, id);
+        assert_eq!(record.name, format!("r{}", id));
+        id += 1;
+    }
+    assert_eq!(id, 1000);
+
+    // 与iter_from_reader一样匹配任意深度的记录
+    let xml = r#"<Dump>
+        <Record id="1"><Name>a</Name></Record>
+        <Group><Part><Record id="2"><Name>b</Name></Record></Part></Group>
+        <Record id="3"><Name>c</Name></Record>
+    </Dump>"#;
+    let mut ids = Vec::new();
+    let mut records = de::iter_from_async_reader::<Record, _>(xml.as_bytes(), "Record");
+    while let Some(record) = records.next().await {
+        ids.push(record.unwrap().id);
+    }
+    let sync_ids = de::iter_from_reader::<Record, _>(xml.as_bytes(), "Record")
+        .map(|record| record.unwrap().id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(ids, sync_ids);
+
+    let options = de::ParseOptions {
+        max_text_len: 4,
+        ..Default::default()
+    };
+    let xml = r#"<Dump><Record id="1"><Name>abcde</Name></Record></Dump>"#;
+    let mut records =
+        de::iter_from_async_reader_with_options::<Record, _>(xml.as_bytes(), "Record", &options);
+    assert!(matches!(
+        records.next().await,
+        Some(Err(de::Error::LimitExceeded(de::Limit::TextLength)))
+    ));
+
+    let xml = r#"<Record id="7"><Name>root</Name></Record>"#;
+    let mut records = de::iter_from_async_reader::<Record, _>(xml.as_bytes(), "Record");
+    assert_eq!(records.next().await.unwrap().unwrap().id, 7);
+    assert!(records.next().await.is_none());
+
+    let mut records =
+        de::iter_from_async_reader::<Record, _>(&b"<Dump><Record id=\"1\">"[..], "Record");
+    assert!(matches!(records.next().await, Some(Err(de::Error::BadXml))));
+    assert!(records.next().await.is_none());
+}
@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{de, se};
+#[test]
+fn test() {
+    // 读取CDATA
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Script {
+            #[easy_xml(text)]
+            code: String,
+            #[easy_xml(text, no_cdata)]
+            plain: String,
+        }
+        let script: Script = de::from_str(r#"<Script>a<![CDATA[<b>]]>c</Script>"#).unwrap();
+        assert_eq!(script.code.as_str(), "a<b>c");
+        assert_eq!(script.plain.as_str(), "ac");
+    }
+    // 子节点中的CDATA
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Node {
+            #[easy_xml(rename = "Script")]
+            script: String,
+        }
+        let node: Node = de::from_str(r#"<Node><Script><![CDATA[a<b]]></Script></Node>"#).unwrap();
+        assert_eq!(node.script.as_str(), "a<b");
+    }
+    // 写成CDATA
+    {
+        #[derive(Debug, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Script {
+            #[easy_xml(text, cdata)]
+            code: String,
+        }
+        let script = Script {
+            code: "if (a < b && c]]>d) {}".to_string(),
+        };
+        let xml = se::to_string(&script).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Script><![CDATA[if (a < b && c]]]]><![CDATA[>d) {}]]></Script>"#
+        );
+
+        let script: Script = de::from_str(xml.as_str()).unwrap();
+        assert_eq!(script.code.as_str(), "if (a < b && c]]>d) {}");
+    }
+}
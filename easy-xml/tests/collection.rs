@@ -0,0 +1,145 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::{BTreeSet, HashSet, VecDeque},
+    rc::Rc,
+    sync::{Mutex, RwLock},
+};
+
+use easy_xml::{de, se, XmlCollection};
+#[test]
+fn test() {
+    // 集合类型的重复节点
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(rename = "a")]
+            deque: VecDeque<u32>,
+            #[easy_xml(rename = "b")]
+            set: BTreeSet<String>,
+            #[easy_xml(rename = "c")]
+            hash: HashSet<u32>,
+            #[easy_xml(rename = "d")]
+            boxed: Box<[i32]>,
+            #[easy_xml(rename = "e")]
+            array: [u8; 2],
+            #[easy_xml(rename = "f")]
+            optional: Option<BTreeSet<u32>>,
+            #[easy_xml(attribute, list)]
+            point: [f64; 3],
+        }
+        let xml = r#"<Node point="1 2.5 3"><a>1</a><a>2</a><b>y</b><b>x</b><c>5</c><c>5</c><d>-1</d><e>7</e><e>8</e></Node>"#;
+        let node: Node = de::from_str(xml).unwrap();
+        assert_eq!(node.deque, VecDeque::from(vec![1, 2]));
+        assert_eq!(node.set.iter().collect::<Vec<_>>(), vec!["x", "y"]);
+        assert_eq!(node.hash.len(), 1);
+        assert_eq!(&*node.boxed, &[-1]);
+        assert_eq!(node.array, [7, 8]);
+        assert_eq!(node.optional, None);
+        assert_eq!(node.point, [1.0, 2.5, 3.0]);
+
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node point="1 2.5 3"><a>1</a><a>2</a><b>x</b><b>y</b><c>5</c><d>-1</d><e>7</e><e>8</e></Node>"#
+        );
+
+        // 数组的项数必须一致
+        let xml = r#"<Node point="1 2 3"><e>7</e></Node>"#;
+        assert!(de::from_str::<Node>(xml).is_err());
+        let xml = r#"<Node point="1 2"><e>7</e><e>8</e></Node>"#;
+        assert!(de::from_str::<Node>(xml).is_err());
+    }
+    // 自定义集合
+    {
+        #[derive(Debug, PartialEq, Default)]
+        struct Stack(Vec<String>);
+        impl XmlCollection for Stack {
+            type Item = String;
+
+            fn from_items(items: Vec<String>) -> Result<Self, de::Error> {
+                Ok(Stack(items))
+            }
+
+            fn items(&self) -> Box<dyn Iterator<Item = &String> + '_> {
+                Box::new(self.0.iter())
+            }
+        }
+
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(rename = "v", repeated)]
+            stack: Stack,
+        }
+        let node: Node = de::from_str(r#"<Node><v>a</v><v>b</v></Node>"#).unwrap();
+        assert_eq!(node.stack, Stack(vec!["a".to_string(), "b".to_string()]));
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node><v>a</v><v>b</v></Node>"#
+        );
+    }
+    // 字符串、字符及指针类型
+    {
+        #[derive(Debug, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(attribute)]
+            cow: Cow<'static, str>,
+            #[easy_xml(attribute)]
+            boxed: Box<str>,
+            #[easy_xml(attribute)]
+            rc: Rc<str>,
+            #[easy_xml(attribute)]
+            c: char,
+            #[easy_xml(rename = "Mutex")]
+            mutex: Mutex<u32>,
+            #[easy_xml(rename = "RwLock")]
+            rw_lock: RwLock<Option<String>>,
+            #[easy_xml(rename = "Cell")]
+            cell: Cell<i32>,
+        }
+        let node: Node = de::from_str(
+            r#"<Node cow="a" boxed="b" rc="c" c=" d "><Mutex>1</Mutex><Cell>-2</Cell></Node>"#,
+        )
+        .unwrap();
+        assert_eq!(node.cow, "a");
+        assert_eq!(&*node.boxed, "b");
+        assert_eq!(&*node.rc, "c");
+        assert_eq!(node.c, 'd');
+        assert_eq!(*node.mutex.lock().unwrap(), 1);
+        assert_eq!(*node.rw_lock.read().unwrap(), None);
+        assert_eq!(node.cell.get(), -2);
+
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node cow="a" boxed="b" rc="c" c="d"><Mutex>1</Mutex><Cell>-2</Cell></Node>"#
+        );
+
+        let xml = r#"<Node cow="" boxed="" rc="" c="de"><Mutex>1</Mutex><Cell>0</Cell></Node>"#;
+        assert!(de::from_str::<Node>(xml).is_err());
+    }
+    #[cfg(feature = "smallvec")]
+    {
+        use smallvec::SmallVec;
+
+        #[derive(Debug, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(rename = "v")]
+            values: SmallVec<[u32; 2]>,
+        }
+        let node: Node = de::from_str(r#"<Node><v>1</v><v>2</v><v>3</v></Node>"#).unwrap();
+        assert_eq!(node.values.as_slice(), &[1, 2, 3]);
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node><v>1</v><v>2</v><v>3</v></Node>"#
+        );
+    }
+}
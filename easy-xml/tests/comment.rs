@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{de, se};
+
+#[test]
+fn test() {
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    struct Item {
+        #[easy_xml(comment)]
+        note: Option<String>,
+        #[easy_xml(attribute)]
+        id: u32,
+    }
+
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Config {
+        #[easy_xml(comment)]
+        header: Vec<String>,
+        #[easy_xml(rename = "Item")]
+        items: Vec<Item>,
+    }
+
+    let xml = r#"<!-- file header --><Config><!-- generated --><Item id="1"><!-- first --></Item><!-- by hand --><Item id="2"/></Config>"#;
+    let config: Config = de::from_str(xml).unwrap();
+    assert_eq!(config.header, vec![" generated ", " by hand "]);
+    assert_eq!(config.items[0].note.as_deref(), Some(" first "));
+    assert_eq!(config.items[1].note, None);
+
+    let out = se::to_string(&config).unwrap();
+    assert_eq!(
+        out.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Config><!-- generated --><!-- by hand --><Item id="1"><!-- first --></Item><Item id="2" /></Config>"#
+    );
+
+    // 单个字符串拼接所有注释，缺少时为空
+    {
+        #[derive(Debug, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(comment)]
+            comment: String,
+        }
+        let node: Node = de::from_str("<Node><!--a--><!--b--></Node>").unwrap();
+        assert_eq!(node.comment.as_str(), "ab");
+        let node: Node = de::from_str("<Node></Node>").unwrap();
+        assert_eq!(node.comment.as_str(), "");
+        // 空字符串不写出注释，往返后不变
+        let out = se::to_string(&node).unwrap();
+        assert_eq!(
+            out.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node />"#
+        );
+        let again: Node = de::from_str(out.as_str()).unwrap();
+        assert_eq!(again.comment.as_str(), "");
+
+        let node = Node {
+            comment: "a--b-".to_string(),
+        };
+        assert_eq!(
+            se::to_string(&node).unwrap().as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node><!--a- -b- --></Node>"#
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use easy_xml::de;
+
+#[macro_use]
+extern crate easy_xml_derive;
+
+#[derive(PartialEq, Debug, XmlDeserialize)]
+struct Node {
+    #[easy_xml(attribute)]
+    name: String,
+    #[easy_xml(text)]
+    text: String,
+}
+
+fn expected() -> Node {
+    Node {
+        name: "名称".to_string(),
+        text: "中文".to_string(),
+    }
+}
+
+#[test]
+fn test_declared_encoding() {
+    let xml = r#"<?xml version="1.0" encoding="GBK"?><Node name="名称">中文</Node>"#;
+    let (bytes, _, _) = encoding_rs::GBK.encode(xml);
+
+    let doc = de::parse_document(bytes.as_ref()).unwrap();
+    assert_eq!(doc.encoding.as_str(), "GBK");
+    assert_eq!(de::from_document::<Node>(&doc).unwrap(), expected());
+
+    let xml = "<?xml version='1.0' encoding='Shift_JIS'?><Node name=\"名称\">中文</Node>";
+    let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(xml);
+    assert_eq!(
+        de::from_bytes::<Node, _>(bytes.as_ref()).unwrap(),
+        expected()
+    );
+
+    let xml = r#"<?xml version="1.0" encoding="windows-1252"?><Node name="café">€</Node>"#;
+    let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(xml);
+    let node = de::from_bytes::<Node, _>(bytes.as_ref()).unwrap();
+    assert_eq!(node.name.as_str(), "café");
+    assert_eq!(node.text.as_str(), "€");
+}
+
+#[test]
+fn test_bom() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-16"?><Node name="名称">中文</Node>"#;
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in xml.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    let doc = de::parse_document(bytes.as_slice()).unwrap();
+    assert_eq!(doc.encoding.as_str(), "UTF-16LE");
+    assert_eq!(de::from_document::<Node>(&doc).unwrap(), expected());
+
+    let mut bytes = vec![0xFE, 0xFF];
+    for unit in xml.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+    assert_eq!(
+        de::from_bytes::<Node, _>(bytes.as_slice()).unwrap(),
+        expected()
+    );
+
+    let mut bytes = vec![0xEF, 0xBB, 0xBF];
+    bytes.extend_from_slice(r#"<Node name="名称">中文</Node>"#.as_bytes());
+    let doc = de::parse_document(bytes.as_slice()).unwrap();
+    assert_eq!(doc.encoding.as_str(), "UTF-8");
+    assert_eq!(de::from_document::<Node>(&doc).unwrap(), expected());
+}
+
+#[test]
+fn test_override_encoding() {
+    let (bytes, _, _) = encoding_rs::GB18030.encode(r#"<Node name="名称">中文</Node>"#);
+    let options = de::ParseOptions {
+        encoding: Some("GB18030".to_string()),
+        ..Default::default()
+    };
+    let node = de::from_bytes_with_options::<Node, _>(bytes.as_ref(), &options).unwrap();
+    assert_eq!(node, expected());
+
+    let options = de::ParseOptions {
+        encoding: Some("NOT-AN-ENCODING".to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        de::from_bytes_with_options::<Node, _>(bytes.as_ref(), &options),
+        Err(de::Error::UnsupportedEncoding(_))
+    ));
+
+    // 字符串已经是UTF-8
+    let node: Node =
+        de::from_str(r#"<?xml version="1.0" encoding="GBK"?><Node name="名称">中文</Node>"#)
+            .unwrap();
+    assert_eq!(node, expected());
+}
@@ -0,0 +1,122 @@
+use easy_xml::{de, se};
+
+#[macro_use]
+extern crate easy_xml_derive;
+
+#[derive(PartialEq, Debug, XmlDeserialize, XmlSerialize)]
+struct Node {
+    #[easy_xml(attribute)]
+    name: String,
+    #[easy_xml(text)]
+    text: String,
+}
+
+#[test]
+fn test_to_bytes_with_encoding() {
+    let node = Node {
+        name: "名称".to_string(),
+        text: "中文€".to_string(),
+    };
+
+    let bytes = se::to_bytes(&node, "GBK").unwrap();
+    let (xml, _, _) = encoding_rs::GBK.decode(&bytes);
+    assert_eq!(
+        xml,
+        r#"<?xml version="1.0" encoding="GBK"?><Node name="名称">中文€</Node>"#
+    );
+    assert!(!bytes.windows(3).any(|w| w == "中".as_bytes()));
+
+    // 无法表示的字符写成数字字符引用
+    let bytes = se::to_bytes(&node, "ISO-8859-1").unwrap();
+    assert_eq!(
+        bytes,
+        br#"<?xml version="1.0" encoding="ISO-8859-1"?><Node name="&#21517;&#31216;">&#20013;&#25991;&#8364;</Node>"#
+    );
+
+    // ISO-8859-1按字节对应U+0000~U+00FF，而不是windows-1252
+    let node = Node {
+        name: "é".to_string(),
+        text: "\u{80}ÿ€".to_string(),
+    };
+    let bytes = se::to_bytes(&node, "latin1").unwrap();
+    let mut expected = br#"<?xml version="1.0" encoding="latin1"?><Node name=""#.to_vec();
+    expected.extend_from_slice(&[0xE9, b'"', b'>', 0x80, 0xFF]);
+    expected.extend_from_slice(b"&#8364;</Node>");
+    assert_eq!(bytes, expected);
+    assert_eq!(de::from_bytes::<Node, _>(bytes.as_slice()).unwrap(), node);
+
+    let bytes = se::to_bytes(&node, "US-ASCII").unwrap();
+    assert_eq!(
+        bytes,
+        br#"<?xml version="1.0" encoding="US-ASCII"?><Node name="&#233;">&#128;&#255;&#8364;</Node>"#
+    );
+    assert_eq!(de::from_bytes::<Node, _>(bytes.as_slice()).unwrap(), node);
+
+    let bytes = se::to_bytes(&node, "UTF-16LE").unwrap();
+    assert_eq!(&bytes[..4], &[0xFF, 0xFE, b'<', 0]);
+
+    match se::to_bytes(&node, "NOT-AN-ENCODING") {
+        Err(se::Error::UnsupportedEncoding(encoding)) => {
+            assert_eq!(encoding.as_str(), "NOT-AN-ENCODING")
+        }
+        _ => panic!("expect UnsupportedEncoding"),
+    }
+
+    // 输出编码不是自身的编码
+    for encoding in ["hz-gb-2312", "iso-2022-kr", "replacement"] {
+        assert!(matches!(
+            se::to_bytes(&node, encoding),
+            Err(se::Error::UnsupportedEncoding(_))
+        ));
+    }
+}
+
+#[test]
+fn test_unencodable_characters() {
+    #[derive(PartialEq, Debug, XmlDeserialize, XmlSerialize)]
+    struct Script {
+        #[easy_xml(text, cdata)]
+        code: String,
+    }
+
+    // CDATA中不能使用字符引用，在无法表示的字符处拆开
+    let script = Script {
+        code: "a<€>]]>b".to_string(),
+    };
+    let bytes = se::to_bytes(&script, "US-ASCII").unwrap();
+    assert_eq!(
+        bytes,
+        br#"<?xml version="1.0" encoding="US-ASCII"?><Script><![CDATA[a<]]>&#8364;<![CDATA[>]]]]><![CDATA[>b]]></Script>"#
+    );
+    assert_eq!(
+        de::from_bytes::<Script, _>(bytes.as_slice()).unwrap(),
+        script
+    );
+
+    #[derive(PartialEq, Debug, XmlSerialize)]
+    struct Commented {
+        #[easy_xml(comment)]
+        comment: String,
+    }
+
+    // 注释和名称无法使用字符引用
+    let commented = Commented {
+        comment: "价格".to_string(),
+    };
+    assert!(matches!(
+        se::to_bytes(&commented, "ISO-8859-1"),
+        Err(se::Error::UnencodableCharacter('价'))
+    ));
+
+    #[derive(PartialEq, Debug, XmlSerialize)]
+    #[easy_xml(rename = "价格")]
+    struct Price {
+        #[easy_xml(text)]
+        value: u32,
+    }
+    assert!(matches!(
+        se::to_bytes(&Price { value: 1 }, "US-ASCII"),
+        Err(se::Error::UnencodableCharacter('价'))
+    ));
+    assert!(se::to_bytes(&Price { value: 1 }, "GBK").is_ok());
+}
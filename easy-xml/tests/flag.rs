@@ -0,0 +1,83 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{de, se};
+#[test]
+fn test() {
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Input {
+        #[easy_xml(attribute)]
+        name: String,
+        #[easy_xml(attribute, flag)]
+        disabled: bool,
+        #[easy_xml(attribute, flag)]
+        checked: bool,
+        #[easy_xml(rename = "Verbose", flag)]
+        verbose: bool,
+        #[easy_xml(rename = "Quiet", flag)]
+        quiet: bool,
+    }
+
+    // 属性或节点存在即为true，与内容无关
+    let xml = r#"<Input name="a" disabled=""><Verbose/></Input>"#;
+    let input: Input = de::from_str(xml).unwrap();
+    assert_eq!(
+        input,
+        Input {
+            name: "a".to_string(),
+            disabled: true,
+            checked: false,
+            verbose: true,
+            quiet: false,
+        }
+    );
+    let xml = r#"<Input name="a" checked="false"><Quiet>no</Quiet></Input>"#;
+    let input: Input = de::from_str(xml).unwrap();
+    assert!(!input.disabled && input.checked && !input.verbose && input.quiet);
+
+    // 只有为true时写出
+    let input = Input {
+        name: "a".to_string(),
+        disabled: true,
+        checked: false,
+        verbose: true,
+        quiet: false,
+    };
+    let xml = se::to_string(&input).unwrap();
+    assert_eq!(
+        xml.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Input name="a" disabled="disabled"><Verbose /></Input>"#
+    );
+    assert_eq!(de::from_str::<Input>(xml.as_str()).unwrap(), input);
+
+    // enum变体中的flag字段
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    enum Setting {
+        Debug {
+            #[easy_xml(attribute, flag)]
+            trace: bool,
+        },
+        Release,
+    }
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Options {
+        #[easy_xml(rename = "Debug|Release")]
+        options: Vec<Setting>,
+    }
+    let xml = r#"<Options><Debug trace="1"/><Release/><Debug/></Options>"#;
+    let options: Options = de::from_str(xml).unwrap();
+    assert_eq!(
+        options.options,
+        vec![
+            Setting::Debug { trace: true },
+            Setting::Release,
+            Setting::Debug { trace: false }
+        ]
+    );
+    let xml = se::to_string(&options).unwrap();
+    assert_eq!(
+        xml.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Options><Debug trace="trace" /><Release /><Debug /></Options>"#
+    );
+}
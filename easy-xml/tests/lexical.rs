@@ -0,0 +1,113 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{
+    de::{self, ParseOptions},
+    format::{BoolFormat, FloatFormat, ValueFormat},
+    se::{self, SerializeSettings},
+};
+#[test]
+fn test() {
+    // 字段上指定格式
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Config {
+            #[easy_xml(attribute, bool_format = "yes_no")]
+            enabled: bool,
+            #[easy_xml(attribute, bool_format = "numeric")]
+            visible: Option<bool>,
+            #[easy_xml(attribute, hex)]
+            mask: u32,
+            #[easy_xml(attribute, hex, plus_sign)]
+            offset: i16,
+            #[easy_xml(rename = "Ratio", precision = 2)]
+            ratio: f64,
+            #[easy_xml(rename = "Max", float_format = "xsd")]
+            max: Vec<f32>,
+            #[easy_xml(attribute, list, bool_format = "yn")]
+            flags: Vec<bool>,
+        }
+        let xml = r#"<Config enabled="Y" visible="1" mask="0x1F" offset="-0x10" flags="y N 1"><Ratio>0.5</Ratio><Max>INF</Max><Max>-inf</Max><Max>NaN</Max></Config>"#;
+        let config: Config = de::from_str(xml).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.visible, Some(true));
+        assert_eq!(config.mask, 31);
+        assert_eq!(config.offset, -16);
+        assert_eq!(config.ratio, 0.5);
+        assert_eq!(config.max[..2], [f32::INFINITY, f32::NEG_INFINITY]);
+        assert!(config.max[2].is_nan());
+        assert_eq!(config.flags, vec![true, false, true]);
+
+        let config = Config {
+            offset: 16,
+            visible: Some(false),
+            ..config
+        };
+        let xml = se::to_string(&config).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Config enabled="yes" visible="0" mask="0x1F" offset="+0x10" flags="Y N Y"><Ratio>0.50</Ratio><Max>INF</Max><Max>-INF</Max><Max>NaN</Max></Config>"#
+        );
+
+        // 十进制仍然可以读取，未指定格式的bool只接受true/false
+        let xml = r#"<Config enabled="no" mask="31" offset="+5"><Ratio>1</Ratio></Config>"#;
+        let config: Config = de::from_str(xml).unwrap();
+        assert_eq!((config.enabled, config.mask, config.offset), (false, 31, 5));
+        let xml = r#"<Config enabled="ok" mask="1" offset="1"><Ratio>1</Ratio></Config>"#;
+        assert!(de::from_str::<Config>(xml).is_err());
+    }
+    // 全局格式，字段上的格式优先
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(attribute)]
+            a: bool,
+            #[easy_xml(attribute, bool_format = "strict")]
+            b: bool,
+            #[easy_xml(attribute)]
+            c: f64,
+            #[easy_xml(attribute)]
+            d: u8,
+        }
+        let xml = r#"<Node a="1" b="true" c="INF" d="0xff"/>"#;
+        assert!(de::from_str::<Node>(xml).is_err());
+
+        let format = ValueFormat {
+            bool_format: BoolFormat::Numeric,
+            float_format: FloatFormat::Xsd,
+            hex: true,
+            ..Default::default()
+        };
+        let options = ParseOptions {
+            format: format.clone(),
+            ..Default::default()
+        };
+        let node: Node = de::from_str_with_options(xml, &options).unwrap();
+        assert_eq!(
+            node,
+            Node {
+                a: true,
+                b: true,
+                c: f64::INFINITY,
+                d: 255
+            }
+        );
+
+        let settings = SerializeSettings {
+            format,
+            ..Default::default()
+        };
+        let xml = se::to_string_with_options(&node, &settings).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node a="1" b="true" c="INF" d="0xFF" />"#
+        );
+        // 调用结束后恢复默认格式
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node a="true" b="true" c="inf" d="255" />"#
+        );
+    }
+}
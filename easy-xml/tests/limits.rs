@@ -0,0 +1,185 @@
+use easy_xml::de::{self, Error, Limit, ParseOptions};
+
+#[macro_use]
+extern crate easy_xml_derive;
+
+#[derive(Debug, XmlDeserialize)]
+struct Node {
+    #[easy_xml(rename = "Node")]
+    children: Vec<Node>,
+}
+
+fn limit_of(result: Result<Node, Error>) -> Limit {
+    match result {
+        Err(Error::LimitExceeded(limit)) => limit,
+        other => panic!("expect LimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_depth() {
+    let xml = "<Node>".repeat(100_000) + &"</Node>".repeat(100_000);
+    assert_eq!(limit_of(de::from_str(&xml)), Limit::Depth);
+
+    let xml = "<Node>".repeat(10) + &"</Node>".repeat(10);
+    let options = ParseOptions {
+        max_depth: 10,
+        ..Default::default()
+    };
+    assert!(de::from_str_with_options::<Node>(&xml, &options).is_ok());
+    let options = ParseOptions {
+        max_depth: 9,
+        ..Default::default()
+    };
+    assert_eq!(
+        limit_of(de::from_str_with_options(&xml, &options)),
+        Limit::Depth
+    );
+}
+
+#[test]
+fn test_attributes_nodes_and_text() {
+    let options = ParseOptions {
+        max_attributes: 2,
+        max_nodes: 3,
+        max_text_len: 8,
+        ..Default::default()
+    };
+
+    let xml = r#"<Node a="1" b="2"><Node/><Node>12345678</Node></Node>"#;
+    let node = de::from_str_with_options::<Node>(xml, &options).unwrap();
+    assert_eq!(node.children.len(), 2);
+
+    let xml = r#"<Node a="1" b="2" c="3"></Node>"#;
+    assert_eq!(
+        limit_of(de::from_str_with_options(xml, &options)),
+        Limit::Attributes
+    );
+
+    let xml = r#"<Node><Node/><Node/><Node/></Node>"#;
+    assert_eq!(
+        limit_of(de::from_str_with_options(xml, &options)),
+        Limit::Nodes
+    );
+
+    let xml = r#"<Node>123456789</Node>"#;
+    assert_eq!(
+        limit_of(de::from_str_with_options(xml, &options)),
+        Limit::TextLength
+    );
+
+    // 属性值由xml-rs在读取时限制
+    let xml = r#"<Node a="123456789"></Node>"#;
+    assert_eq!(
+        limit_of(de::from_str_with_options(xml, &options)),
+        Limit::TextLength
+    );
+
+    // 属性数量同样由xml-rs在读取时限制，命名空间声明不计入
+    let xml = r#"<Node xmlns:x="u" a="1" x:b="2"/>"#;
+    assert!(de::from_str_with_options::<Node>(xml, &options).is_ok());
+    let attributes = (0..100_000)
+        .map(|i| format!("a{}=\"{}\"", i, i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let xml = format!("<Node {}/>", attributes);
+    assert_eq!(
+        limit_of(de::from_str_with_options(&xml, &options)),
+        Limit::Attributes
+    );
+    let xml = format!("<Root><Node {}/></Root>", attributes);
+    let mut iter = de::iter_from_reader_with_options::<Node, _>(xml.as_bytes(), "Node", &options);
+    assert_eq!(limit_of(iter.next().unwrap()), Limit::Attributes);
+
+    // 标签外像是属性的文本
+    let xml = r#"<Node a="1"><!-- <x a="1" b="2" c="3" --></Node>"#;
+    assert_eq!(
+        limit_of(de::from_str_with_options(xml, &options)),
+        Limit::TextLength
+    );
+}
+
+#[test]
+fn test_entity_expansion() {
+    let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE Node [
+  <!ENTITY a "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa">
+  <!ENTITY b "&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;">
+  <!ENTITY c "&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;">
+  <!ENTITY d "&c;&c;&c;&c;&c;&c;&c;&c;&c;&c;">
+]>
+<Node>&d;</Node>"#;
+    assert_eq!(limit_of(de::from_str(xml)), Limit::EntityExpansion);
+}
+
+#[test]
+fn test_streaming() {
+    let options = ParseOptions {
+        max_depth: 3,
+        max_nodes: 2,
+        max_text_len: 8,
+        ..Default::default()
+    };
+
+    // max_nodes按每条记录计算
+    let xml = r#"<Root><Node><Node/></Node><Node><Node/></Node></Root>"#;
+    let records = de::iter_from_reader_with_options::<Node, _>(xml.as_bytes(), "Node", &options)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+
+    let xml = r#"<Root><Node><Node/><Node/></Node></Root>"#;
+    let mut iter = de::iter_from_reader_with_options::<Node, _>(xml.as_bytes(), "Node", &options);
+    assert_eq!(limit_of(iter.next().unwrap()), Limit::Nodes);
+    assert!(iter.next().is_none());
+
+    // 记录外的节点同样计入深度
+    let xml = r#"<Root><Group><Node><Node/></Node></Group></Root>"#;
+    let mut iter = de::iter_from_reader_with_options::<Node, _>(xml.as_bytes(), "Node", &options);
+    assert_eq!(limit_of(iter.next().unwrap()), Limit::Depth);
+
+    let xml = r#"<Root><Node>123456789</Node></Root>"#;
+    let mut iter = de::iter_from_reader_with_options::<Node, _>(xml.as_bytes(), "Node", &options);
+    assert_eq!(limit_of(iter.next().unwrap()), Limit::TextLength);
+
+    let mut parser = de::Parser::with_options(&options);
+    assert!(parser.feed(b"<Root><Node><Node/></Node>").is_ok());
+    assert!(matches!(
+        parser.feed(b"<Node><Node><Node>"),
+        Err(Error::LimitExceeded(Limit::Depth))
+    ));
+
+    let mut parser = de::Parser::with_options(&options);
+    assert!(matches!(
+        parser.feed(b"<Root><Node>123456789</Node>"),
+        Err(Error::LimitExceeded(Limit::TextLength))
+    ));
+
+    // 未接收完整的子节点不能无限缓存
+    let options = ParseOptions {
+        max_pending_len: 64,
+        ..Default::default()
+    };
+    let mut parser = de::Parser::with_options(&options);
+    assert!(parser.feed(b"<Root><Node>").is_ok());
+    let mut result = Ok(vec![]);
+    for _ in 0..16 {
+        result = parser.feed(b"0123456789");
+        if result.is_err() {
+            break;
+        }
+    }
+    assert!(matches!(
+        result,
+        Err(Error::LimitExceeded(Limit::PendingLength))
+    ));
+
+    // 子节点之间的文本不会累积
+    let mut parser = de::Parser::with_options(&options);
+    assert!(parser.feed(b"<Root>").is_ok());
+    for _ in 0..16 {
+        assert!(parser.feed(b"0123456789").unwrap().is_empty());
+    }
+    let events = parser.feed(b"<Node/></Root>").unwrap();
+    assert_eq!(events.len(), 2);
+}
@@ -0,0 +1,73 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::collections::HashSet;
+
+use easy_xml::{de, se};
+#[test]
+fn test() {
+    // 空白分隔的属性
+    {
+        #[derive(Debug, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(attribute, list)]
+            class: Vec<String>,
+            #[easy_xml(attribute, list, separator = ",")]
+            ids: Vec<u32>,
+            #[easy_xml(attribute, list)]
+            tags: HashSet<String>,
+        }
+        let node: Node =
+            de::from_str(r#"<Node class=" a  b c " ids="1, 2,3" tags="x y x"></Node>"#).unwrap();
+        assert_eq!(node.class, vec!["a", "b", "c"]);
+        assert_eq!(node.ids, vec![1, 2, 3]);
+        assert_eq!(node.tags.len(), 2);
+        assert!(node.tags.contains("x") && node.tags.contains("y"));
+
+        let node = Node {
+            class: vec!["a".to_string(), "b".to_string()],
+            ids: vec![1, 2],
+            tags: HashSet::new(),
+        };
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node class="a b" ids="1,2" tags="" />"#
+        );
+    }
+    // 缺少属性时为空集合
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Node {
+            #[easy_xml(attribute, list)]
+            class: Vec<String>,
+        }
+        let node: Node = de::from_str(r#"<Node></Node>"#).unwrap();
+        assert!(node.class.is_empty());
+    }
+    // 文本和子节点
+    {
+        #[derive(Debug, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(rename = "Values", list, separator = ";")]
+            values: Vec<f64>,
+            #[easy_xml(text, list)]
+            words: Vec<String>,
+        }
+        let node: Node =
+            de::from_str(r#"<Node>hello <Values>1.5;2</Values> world</Node>"#).unwrap();
+        assert_eq!(node.values, vec![1.5, 2.0]);
+        assert_eq!(node.words, vec!["hello", "1.5;2", "world"]);
+
+        let node = Node {
+            values: vec![1.5, 2.0],
+            words: vec!["hello".to_string(), "world".to_string()],
+        };
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node>hello world<Values>1.5;2</Values></Node>"#
+        );
+    }
+}
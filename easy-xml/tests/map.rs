@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::collections::{BTreeMap, HashMap};
+
+use easy_xml::{de, se};
+use indexmap::IndexMap;
+
+#[test]
+fn test() {
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Config {
+        #[easy_xml(rename = "Property", map(key = "@name"))]
+        properties: BTreeMap<String, String>,
+        #[easy_xml(rename = "Settings", map(key = tag))]
+        settings: IndexMap<String, u32>,
+        #[easy_xml(rename = "Limit", map(key = "@id"))]
+        limits: HashMap<u32, f64>,
+    }
+
+    let xml = r#"<Config>
+      <Property name="b">2</Property>
+      <Property name="a">1</Property>
+      <Settings><width>10</width><height>20</height></Settings>
+      <Limit id="7">1.5</Limit>
+    </Config>"#;
+    let config: Config = de::from_str(xml).unwrap();
+    assert_eq!(config.properties.get("a").map(String::as_str), Some("1"));
+    assert_eq!(config.properties.get("b").map(String::as_str), Some("2"));
+    assert_eq!(
+        config.settings.iter().collect::<Vec<_>>(),
+        vec![(&"width".to_string(), &10), (&"height".to_string(), &20)]
+    );
+    assert_eq!(config.limits.get(&7), Some(&1.5));
+
+    let out = se::to_string(&config).unwrap();
+    assert_eq!(
+        out.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Config><Property name="a">1</Property><Property name="b">2</Property><Settings><width>10</width><height>20</height></Settings><Limit id="7">1.5</Limit></Config>"#
+    );
+
+    // 缺少时为空，缺少键属性时报错
+    {
+        let config: Config = de::from_str("<Config/>").unwrap();
+        assert!(config.properties.is_empty() && config.settings.is_empty());
+        assert!(de::from_str::<Config>(r#"<Config><Property>1</Property></Config>"#).is_err());
+    }
+}
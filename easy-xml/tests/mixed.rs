@@ -0,0 +1,84 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{de, se};
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+enum Inline {
+    #[easy_xml(text)]
+    Text(String),
+    #[easy_xml(rename = "b")]
+    Bold {
+        #[easy_xml(text)]
+        text: String,
+    },
+    #[easy_xml(rename = "br")]
+    Break,
+    #[easy_xml(rename = "a")]
+    Link {
+        #[easy_xml(attribute)]
+        href: String,
+        #[easy_xml(mixed)]
+        content: Vec<Inline>,
+    },
+}
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+#[easy_xml(root, rename = "p")]
+struct Paragraph {
+    #[easy_xml(attribute)]
+    id: String,
+    #[easy_xml(mixed)]
+    content: Vec<Inline>,
+}
+
+#[test]
+fn test() {
+    let xml =
+        r#"<p id="1">Hello <b>world</b> and <a href="/x">see <b>this</b></a>!<i>skip</i></p>"#;
+    let p: Paragraph = de::from_str(xml).unwrap();
+    assert_eq!(
+        p.content,
+        vec![
+            Inline::Text("Hello ".to_string()),
+            Inline::Bold {
+                text: "world".to_string()
+            },
+            Inline::Text(" and ".to_string()),
+            Inline::Link {
+                href: "/x".to_string(),
+                content: vec![
+                    Inline::Text("see ".to_string()),
+                    Inline::Bold {
+                        text: "this".to_string()
+                    },
+                ],
+            },
+            Inline::Text("!".to_string()),
+        ]
+    );
+
+    let out = se::to_string(&p).unwrap();
+    assert_eq!(
+        out.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><p id="1">Hello <b>world</b> and <a href="/x">see <b>this</b></a>!</p>"#
+    );
+
+    let again: Paragraph = de::from_str(out.as_str()).unwrap();
+    assert_eq!(again, p);
+
+    // 文本只由text变体读取，即使与单元变体的名称相同；连续的文本和CDATA合并为一段
+    let xml = r#"<p id="2">br<br/>line <![CDATA[<2>]]></p>"#;
+    let p: Paragraph = de::from_str(xml).unwrap();
+    assert_eq!(
+        p.content,
+        vec![
+            Inline::Text("br".to_string()),
+            Inline::Break,
+            Inline::Text("line <2>".to_string()),
+        ]
+    );
+
+    // 与变体匹配但读取失败的节点返回错误
+    let xml = r#"<p id="3">see <a>x</a></p>"#;
+    assert!(de::from_str::<Paragraph>(xml).is_err());
+}
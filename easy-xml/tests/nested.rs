@@ -0,0 +1,129 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{de, se};
+#[test]
+fn test() {
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    struct Item {
+        #[easy_xml(text)]
+        name: String,
+    }
+    // 可选的列表：没有container时为None，container为空时为Some(vec![])
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Order {
+            #[easy_xml(rename = "Items", container)]
+            items: Option<Vec<Item>>,
+        }
+        let order: Order = de::from_str(r#"<Order></Order>"#).unwrap();
+        assert_eq!(order.items, None);
+        let order: Order = de::from_str(r#"<Order><Items/></Order>"#).unwrap();
+        assert_eq!(order.items, Some(vec![]));
+        let order: Order = de::from_str(r#"<Order><Items><Item>a</Item></Items></Order>"#).unwrap();
+        assert_eq!(
+            order.items,
+            Some(vec![Item {
+                name: "a".to_string()
+            }])
+        );
+
+        let xml = se::to_string(&Order { items: None }).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Order />"#
+        );
+        let xml = se::to_string(&Order {
+            items: Some(vec![]),
+        })
+        .unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Order><Items /></Order>"#
+        );
+    }
+    // 没有container时，有匹配的节点才为Some
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Order {
+            #[easy_xml(rename = "Item")]
+            items: Option<Vec<Item>>,
+            #[easy_xml(attribute, list)]
+            ids: Option<Vec<u32>>,
+        }
+        let order: Order = de::from_str(r#"<Order></Order>"#).unwrap();
+        assert_eq!(order.items, None);
+        assert_eq!(order.ids, None);
+        let order: Order =
+            de::from_str(r#"<Order ids="1 2"><Item>a</Item><Item>b</Item></Order>"#).unwrap();
+        assert_eq!(order.items.unwrap().len(), 2);
+        assert_eq!(order.ids, Some(vec![1, 2]));
+    }
+    // Vec<Option<T>>和Box<Option<T>>
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Node {
+            #[easy_xml(rename = "v")]
+            values: Vec<Option<i32>>,
+            #[easy_xml(rename = "Note")]
+            note: Box<Option<String>>,
+            #[easy_xml(attribute)]
+            id: Option<Option<u32>>,
+        }
+        let node: Node = de::from_str(r#"<Node><v>1</v><v>x</v><v>3</v></Node>"#).unwrap();
+        assert_eq!(node.values, vec![Some(1), None, Some(3)]);
+        assert_eq!(node.note, Box::new(None));
+        assert_eq!(node.id, None);
+
+        let node = Node {
+            values: vec![Some(1), None],
+            note: Box::new(Some("a".to_string())),
+            id: None,
+        };
+        let xml = se::to_string(&node).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node><v>1</v><v /><Note>a</Note></Node>"#
+        );
+        assert_eq!(de::from_str::<Node>(xml.as_str()).unwrap(), node);
+    }
+    // Vec<Vec<T>>：item指定内层节点的名称
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Matrix {
+            #[easy_xml(rename = "row", item = "v")]
+            rows: Vec<Vec<i32>>,
+            #[easy_xml(rename = "Group")]
+            groups: Vec<Vec<Item>>,
+        }
+        let xml = r#"<Matrix><row><v>1</v><v>2</v></row><row/><row><v>3</v></row><Group><Item>a</Item><Item>b</Item></Group></Matrix>"#;
+        let matrix: Matrix = de::from_str(xml).unwrap();
+        assert_eq!(matrix.rows, vec![vec![1, 2], vec![], vec![3]]);
+        assert_eq!(matrix.groups.len(), 1);
+        assert_eq!(matrix.groups[0].len(), 2);
+
+        let xml = se::to_string(&matrix).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Matrix><row><v>1</v><v>2</v></row><row /><row><v>3</v></row><Group><Item>a</Item><Item>b</Item></Group></Matrix>"#
+        );
+        assert_eq!(de::from_str::<Matrix>(xml.as_str()).unwrap(), matrix);
+    }
+    // 内层项没有名称时写出失败，不把文本合并到外层节点中
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct M {
+            #[easy_xml(rename = "row")]
+            rows: Vec<Vec<i32>>,
+        }
+        let m = M {
+            rows: vec![vec![1, 2], vec![3]],
+        };
+        assert!(matches!(se::to_string(&m), Err(se::Error::UnnamedNode)));
+        let m = M { rows: vec![vec![]] };
+        assert!(se::to_string(&m).is_ok());
+    }
+}
@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{de, se, OwnedAttribute, XmlElement};
+
+#[test]
+fn test() {
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Config {
+        #[easy_xml(attribute)]
+        version: String,
+        #[easy_xml(rename = "Name")]
+        name: String,
+        #[easy_xml(other_attributes)]
+        extra_attrs: Vec<OwnedAttribute>,
+        #[easy_xml(other_elements)]
+        extra: Vec<XmlElement>,
+    }
+
+    let xml = r#"<Config vendor="acme" version="1"><Vendor><Id>7</Id></Vendor><Name>a</Name><Flag/></Config>"#;
+    let mut config: Config = de::from_str(xml).unwrap();
+    assert_eq!(config.version.as_str(), "1");
+    assert_eq!(config.extra_attrs.len(), 1);
+    assert_eq!(config.extra_attrs[0].name.local_name.as_str(), "vendor");
+    assert_eq!(config.extra.len(), 2);
+
+    config.name = "b".to_string();
+    let out = se::to_string(&config).unwrap();
+    assert_eq!(
+        out.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Config version="1" vendor="acme"><Name>b</Name><Vendor><Id>7</Id></Vendor><Flag /></Config>"#
+    );
+}
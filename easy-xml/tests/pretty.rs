@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use easy_xml::{
+    de,
+    se::{self, SerializeSettings},
+};
+
+#[test]
+fn test() {
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    struct Item {
+        #[easy_xml(attribute)]
+        id: u32,
+        #[easy_xml(rename = "Name")]
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct List {
+        #[easy_xml(rename = "Item")]
+        items: Vec<Item>,
+    }
+
+    let list = List {
+        items: vec![
+            Item {
+                id: 1,
+                name: "a".to_string(),
+            },
+            Item {
+                id: 2,
+                name: "b".to_string(),
+            },
+        ],
+    };
+
+    let settings = SerializeSettings {
+        pretty_format: true,
+        indent: 2,
+        ..Default::default()
+    };
+    let xml = se::to_string_with_options(&list, &settings).unwrap();
+    assert!(xml.contains("\n  <Item id=\"1\">\n    <Name>a</Name>\n  </Item>"));
+    let again: List = de::from_str(xml.as_str()).unwrap();
+    assert_eq!(again, list);
+}
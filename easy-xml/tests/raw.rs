@@ -0,0 +1,107 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::{cell::RefCell, rc::Rc};
+
+use easy_xml::{
+    de::{self, ParseOptions},
+    se, RawXml, XmlElement, XmlNode,
+};
+
+#[test]
+fn test() {
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Envelope {
+        #[easy_xml(rename = "Extension")]
+        extension: XmlElement,
+        #[easy_xml(rename = "Meta")]
+        meta: Rc<RefCell<XmlNode>>,
+        #[easy_xml(rename = "Signed")]
+        signed: RawXml,
+    }
+
+    let signed = r#"<ds:Info  id='x'>中文 &amp; b</ds:Info> <Data></Data>
+  <!-- c --><![CDATA[<d>]]>"#;
+    let xml = format!(
+        r#"<Envelope xmlns:ds="urn:ds"><Extension kind="a"><Item>1</Item></Extension><Meta><Id>2</Id></Meta><Signed>{}</Signed></Envelope>"#,
+        signed
+    );
+    let options = ParseOptions {
+        keep_source: true,
+        ..Default::default()
+    };
+    let envelope: Envelope = de::from_str_with_options(&xml, &options).unwrap();
+
+    match &envelope.extension {
+        XmlElement::Node(node) => {
+            assert_eq!(node.borrow().name.local_name.as_str(), "Extension");
+            assert_eq!(node.borrow().attributes[0].value.as_str(), "a");
+        }
+        _ => panic!("expected a node"),
+    }
+    assert_eq!(envelope.meta.borrow().elements.len(), 1);
+    // 与输入逐字节相同
+    assert_eq!(envelope.signed.xml.as_str(), signed);
+
+    let out = se::to_string(&envelope).unwrap();
+    assert_eq!(
+        out,
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><Envelope><Extension xmlns:ds="urn:ds" kind="a"><Item>1</Item></Extension><Meta xmlns:ds="urn:ds"><Id>2</Id></Meta><Signed xmlns:ds="urn:ds">{}</Signed></Envelope>"#,
+            signed
+        )
+    );
+    // 祖先节点上声明的前缀在写出时仍有定义
+    assert!(de::parse_document(out.as_bytes()).is_ok());
+
+    // 非UTF-8输入取解码后的文本
+    let gbk = format!(r#"<?xml version="1.0" encoding="GBK"?>{}"#, xml);
+    let (bytes, _, _) = encoding_rs::GBK.encode(&gbk);
+    let again: Envelope = de::from_bytes_with_options(&bytes[..], &options).unwrap();
+    assert_eq!(again.signed.xml.as_str(), signed);
+
+    // 默认不保留源文本，按节点树生成
+    let again: Envelope = de::from_str(&xml).unwrap();
+    assert_eq!(
+        again.signed.xml.as_str(),
+        "<ds:Info xmlns:ds=\"urn:ds\" id=\"x\">中文 &amp; b</ds:Info> <Data/>\n  <!-- c --><![CDATA[<d>]]>"
+    );
+
+    // 自行构建的节点没有源文本，按节点树生成
+    let node = XmlNode::empty().into_ref();
+    node.borrow_mut()
+        .elements
+        .push(XmlElement::Text("a < b".to_string()));
+    let raw: RawXml = easy_xml::XmlDeserialize::deserialize(&XmlElement::Node(node)).unwrap();
+    assert_eq!(raw.xml.as_str(), "a &lt; b");
+}
+
+#[test]
+fn test_namespace() {
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Env {
+        #[easy_xml(rename = "Sig")]
+        sig: RawXml,
+    }
+
+    let options = ParseOptions {
+        keep_source: true,
+        ..Default::default()
+    };
+    let env: Env = de::from_str_with_options(
+        r#"<Env xmlns:ds="u"><Sig><ds:X>t</ds:X></Sig></Env>"#,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(env.sig.xml.as_str(), "<ds:X>t</ds:X>");
+    assert_eq!(env.sig.namespace.get("ds"), Some("u"));
+
+    let out = se::to_string(&env).unwrap();
+    assert_eq!(
+        out,
+        r#"<?xml version="1.0" encoding="UTF-8"?><Env><Sig xmlns:ds="u"><ds:X>t</ds:X></Sig></Env>"#
+    );
+    let again: Env = de::from_str_with_options(&out, &options).unwrap();
+    assert_eq!(again.sig, env.sig);
+}
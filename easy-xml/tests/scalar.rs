@@ -0,0 +1,102 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    num::NonZeroU32,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use easy_xml::{de, impl_xml_scalar, se};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct UserId(u64);
+
+impl FromStr for UserId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("u-") {
+            Some(id) => id.parse().map(UserId).map_err(|_| "bad id".to_string()),
+            None => Err(format!("missing prefix in {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "u-{}", self.0)
+    }
+}
+
+// 只实现了FromStr和Display的类型，通过字段参数读写
+#[derive(Debug, PartialEq)]
+struct Tag(String);
+
+impl FromStr for Tag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Tag(s.to_uppercase()))
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_lowercase())
+    }
+}
+
+impl_xml_scalar!(UserId);
+
+#[test]
+fn test() {
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Node {
+        #[easy_xml(attribute)]
+        id: UserId,
+        #[easy_xml(attribute)]
+        ip: IpAddr,
+        #[easy_xml(attribute)]
+        count: NonZeroU32,
+        #[easy_xml(rename = "Path")]
+        path: PathBuf,
+        #[easy_xml(attribute, from_str, display)]
+        tag: Tag,
+        #[easy_xml(attribute, from_str, display)]
+        label: Option<Tag>,
+        #[easy_xml(rename = "Tag", from_str, display)]
+        tags: Vec<Tag>,
+    }
+    let xml = r#"<Node id=" u-7 " ip="127.0.0.1" count="3" tag="a"><Path>/tmp/a.xml</Path><Tag>b</Tag><Tag>c</Tag></Node>"#;
+    let node: Node = de::from_str(xml).unwrap();
+    assert_eq!(
+        node,
+        Node {
+            id: UserId(7),
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            count: NonZeroU32::new(3).unwrap(),
+            path: PathBuf::from("/tmp/a.xml"),
+            tag: Tag("A".to_string()),
+            label: None,
+            tags: vec![Tag("B".to_string()), Tag("C".to_string())],
+        }
+    );
+
+    let xml = se::to_string(&node).unwrap();
+    assert_eq!(
+        xml.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Node id="u-7" ip="127.0.0.1" count="3" tag="a"><Path>/tmp/a.xml</Path><Tag>b</Tag><Tag>c</Tag></Node>"#
+    );
+
+    // 保留解析错误信息
+    let xml = r#"<Node id="7" ip="127.0.0.1" count="3" tag="a"><Path/></Node>"#;
+    match de::from_str::<Node>(xml) {
+        Err(de::Error::Other(msg)) => assert!(msg.contains("missing prefix in 7"), "{}", msg),
+        other => panic!("{:?}", other),
+    }
+    let xml = r#"<Node id="u-7" ip="127.0.0.1" count="0" tag="a"><Path/></Node>"#;
+    assert!(de::from_str::<Node>(xml).is_err());
+}
@@ -1,4 +1,4 @@
-use easy_xml::se;
+use easy_xml::{de, se};
 
 #[macro_use]
 extern crate easy_xml_derive;
@@ -24,3 +24,63 @@ fn test_for_option() {
         r#"<?xml version="1.0" encoding="UTF-8"?><Node><Child>test</Child></Node>"#
     );
 }
+
+#[test]
+fn test_for_serde() {
+    #[derive(PartialEq, Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Node {
+        #[easy_xml(rename = "Item", skip_serializing_if = "Vec::is_empty")]
+        items: Vec<String>,
+        #[easy_xml(attribute, skip_serializing_if = "Option::is_none")]
+        id: Option<u32>,
+        #[easy_xml(attribute, skip_deserializing)]
+        version: u32,
+        #[easy_xml(rename = "Cache", skip_serializing)]
+        cache: String,
+        #[easy_xml(skip_serializing, skip_deserializing)]
+        count: usize,
+        // skip只在序列化时跳过
+        #[easy_xml(rename = "Note", skip)]
+        note: String,
+    }
+
+    // skip_deserializing的字段取默认值，非Option字段也不报错
+    let node: Node = de::from_str(
+        r#"<Node id="1" version="3" count="5"><Item>a</Item><Cache>c</Cache><count>5</count><Note>n</Note></Node>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        node,
+        Node {
+            items: vec!["a".to_string()],
+            id: Some(1),
+            version: 0,
+            cache: "c".to_string(),
+            count: 0,
+            note: "n".to_string(),
+        }
+    );
+
+    let node = Node {
+        version: 2,
+        count: 7,
+        ..node
+    };
+    let xml = se::to_string(&node).unwrap();
+    assert_eq!(
+        xml.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Node id="1" version="2"><Item>a</Item></Node>"#
+    );
+
+    let node = Node {
+        items: vec![],
+        id: None,
+        ..node
+    };
+    let xml = se::to_string(&node).unwrap();
+    assert_eq!(
+        xml.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Node version="2" />"#
+    );
+}
@@ -0,0 +1,152 @@
+use easy_xml::{de, se, XmlDeserialize};
+
+#[macro_use]
+extern crate easy_xml_derive;
+
+#[derive(PartialEq, Debug, XmlDeserialize)]
+struct Record {
+    #[easy_xml(attribute)]
+    id: u32,
+    #[easy_xml(rename = "Name")]
+    name: String,
+}
+
+#[test]
+fn test_iter_from_reader() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+      <Dump>
+        <Meta><Count>3</Count></Meta>
+        <record id="1"><Name>a</Name></record>
+        <record id="2"><Name>b</Name></record>
+        <Group>
+          <record id="3"><Name>c</Name></record>
+        </Group>
+      </Dump>
+    "#;
+
+    let records = de::iter_from_reader::<Record, _>(xml.as_bytes(), "record")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        records,
+        vec![
+            Record {
+                id: 1,
+                name: "a".to_string()
+            },
+            Record {
+                id: 2,
+                name: "b".to_string()
+            },
+            Record {
+                id: 3,
+                name: "c".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_iter_from_reader_with_err() {
+    let xml = r#"<Dump><record id="1"><Name>a</Name></record><record id="x"><Name>b</Name></record><record id="3">"#;
+
+    let mut iter = de::iter_from_reader::<Record, _>(xml.as_bytes(), "record");
+    assert_eq!(iter.next().unwrap().unwrap().id, 1);
+    assert!(iter.next().unwrap().is_err());
+    assert!(matches!(iter.next(), Some(Err(de::Error::XmlError(_)))));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_stream_writer() {
+    #[derive(PartialEq, Debug, XmlSerialize)]
+    struct Row {
+        #[easy_xml(attribute)]
+        id: u32,
+        #[easy_xml(rename = "Name")]
+        name: String,
+    }
+
+    let mut writer = se::StreamWriter::new(Vec::new(), "Rows").unwrap();
+    for id in 1..=2 {
+        writer
+            .write_item(&Row {
+                id,
+                name: format!("row{}", id),
+            })
+            .unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+    assert_eq!(
+        String::from_utf8(bytes).unwrap().as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Rows><Row id="1"><Name>row1</Name></Row><Row id="2"><Name>row2</Name></Row></Rows>"#
+    );
+
+    let writer = se::StreamWriter::new(Vec::new(), "Rows").unwrap();
+    let bytes = writer.finish().unwrap();
+    assert_eq!(
+        String::from_utf8(bytes).unwrap().as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Rows />"#
+    );
+}
+
+#[test]
+fn test_push_parser() {
+    #[derive(PartialEq, Debug, XmlDeserialize)]
+    struct Message {
+        #[easy_xml(attribute)]
+        to: String,
+        #[easy_xml(prefix = "x", rename = "body")]
+        body: String,
+    }
+
+    let xml = r#"<?xml version="1.0"?><stream:stream xmlns:stream="http://etherx.jabber.org/streams" xmlns:x="urn:x" id="s1">
+      <message to="a"><x:body>hi</x:body><note><![CDATA[</message>]]></note></message>
+      <!-- ping -->
+      <message to="b"><x:body a='>'>yo</x:body></message>
+      <presence/>
+    </stream:stream>"#;
+
+    // 按各种分块大小喂入，结果应该一致
+    for chunk in 1..xml.len() {
+        let mut parser = de::Parser::new();
+        let mut events = Vec::new();
+        for data in xml.as_bytes().chunks(chunk) {
+            events.extend(parser.feed(data).unwrap());
+        }
+        assert!(parser.is_closed());
+        assert_eq!(events.len(), 5);
+
+        match &events[0] {
+            de::StreamEvent::Start(easy_xml::XmlElement::Node(root)) => {
+                let root = root.borrow();
+                assert_eq!(root.name.local_name.as_str(), "stream");
+                assert_eq!(root.attributes[0].value.as_str(), "s1");
+                assert!(root.elements.is_empty());
+            }
+            _ => panic!("expect stream start"),
+        }
+        let messages = events[1..3]
+            .iter()
+            .map(|e| match e {
+                de::StreamEvent::Element(element) => Message::deserialize(element).unwrap(),
+                _ => panic!("expect element"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                Message {
+                    to: "a".to_string(),
+                    body: "hi".to_string()
+                },
+                Message {
+                    to: "b".to_string(),
+                    body: "yo".to_string()
+                }
+            ]
+        );
+        assert!(matches!(events[3], de::StreamEvent::Element(_)));
+        assert!(matches!(events[4], de::StreamEvent::End));
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "sync")]
+
+use std::{collections::HashMap, sync::Arc, thread};
+
+use easy_xml::{de, se, XmlDocument};
+
+#[macro_use]
+extern crate easy_xml_derive;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_send_sync() {
+    assert_send_sync::<XmlDocument>();
+    assert_send_sync::<easy_xml::XmlElement>();
+}
+
+#[test]
+fn test_cross_thread() {
+    #[derive(PartialEq, Debug, XmlDeserialize, XmlSerialize)]
+    struct Node {
+        #[easy_xml(attribute)]
+        id: u32,
+        #[easy_xml(rename = "Child")]
+        children: Vec<String>,
+    }
+
+    let doc = thread::spawn(|| {
+        de::parse_document(r#"<Node id="1"><Child>a</Child><Child>b</Child></Node>"#.as_bytes())
+            .unwrap()
+    })
+    .join()
+    .unwrap();
+
+    let mut cache = HashMap::new();
+    cache.insert("node", Arc::new(doc));
+    let cache = Arc::new(cache);
+
+    let handles = (0..4)
+        .map(|_| {
+            let cache = cache.clone();
+            thread::spawn(move || de::from_document::<Node>(&cache["node"]).unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        let node = handle.join().unwrap();
+        assert_eq!(node.id, 1);
+        assert_eq!(node.children, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            se::to_string(&node).unwrap().as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Node id="1"><Child>a</Child><Child>b</Child></Node>"#
+        );
+    }
+}
+
+#[test]
+fn test_node_ref_field() {
+    #[derive(Debug, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Node {
+        #[easy_xml(rename = "Meta")]
+        meta: easy_xml::XmlNodeRef,
+    }
+
+    let node: Node = de::from_str(r#"<Node><Meta><Id>2</Id></Meta></Node>"#).unwrap();
+    let node = thread::spawn(move || node).join().unwrap();
+    assert_eq!(
+        se::to_string(&node).unwrap().as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Node><Meta><Id>2</Id></Meta></Node>"#
+    );
+}
@@ -74,3 +74,54 @@ fn test() {
         }
     }
 }
+
+#[test]
+fn test_text_mode() {
+    // 只取节点自身的文本
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Node {
+            #[easy_xml(text(own))]
+            own: String,
+            #[easy_xml(text(deep))]
+            deep: String,
+        }
+        let node: Node = de::from_str("<Node>a<Node1>text</Node1>b</Node>").unwrap();
+        assert_eq!(node.own.as_str(), "ab");
+        assert_eq!(node.deep.as_str(), "atextb");
+    }
+    // 去除空白
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Node {
+            #[easy_xml(text, normalize_space)]
+            text: String,
+            #[easy_xml(attribute, trim)]
+            count: u32,
+            #[easy_xml(rename = "Age", trim)]
+            age: u8,
+        }
+        let node: Node =
+            de::from_str("<Node count=\" 12 \">  hello\n   world  <Age>\n  18\n</Age></Node>")
+                .unwrap();
+        assert_eq!(node.text.as_str(), "hello world 18");
+        assert_eq!(node.count, 12);
+        assert_eq!(node.age, 18);
+    }
+    // xml:space="preserve"
+    {
+        #[derive(Debug, XmlDeserialize)]
+        struct Node {
+            #[easy_xml(rename = "Code", trim)]
+            code: String,
+            #[easy_xml(rename = "Name", trim)]
+            name: String,
+        }
+        let node: Node = de::from_str(
+            "<Node xml:space=\"preserve\"><Code>  a  </Code><Name xml:space=\"default\"> b </Name></Node>",
+        )
+        .unwrap();
+        assert_eq!(node.code.as_str(), "  a  ");
+        assert_eq!(node.name.as_str(), "b");
+    }
+}
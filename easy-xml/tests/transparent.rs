@@ -0,0 +1,143 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::{fmt, str::FromStr};
+
+use easy_xml::{de, se};
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+#[easy_xml(transparent)]
+struct Meters(f64);
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+#[easy_xml(transparent)]
+struct Name {
+    value: Option<String>,
+}
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+#[easy_xml(transparent)]
+struct Ids(#[easy_xml(list)] Vec<u32>);
+
+#[derive(Debug, PartialEq)]
+struct Color(u8, u8, u8);
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rgb = u32::from_str_radix(s.trim_start_matches('#'), 16).map_err(|e| e.to_string())?;
+        Ok(Color((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+#[easy_xml(transparent)]
+struct Background(#[easy_xml(from_str, display)] Color);
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+#[easy_xml(rename = "Point")]
+struct Point(
+    #[easy_xml(attribute, rename = "label")] Option<String>,
+    Coordinate,
+    Coordinate,
+);
+
+#[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+enum Coordinate {
+    X(#[easy_xml(text)] f64),
+    Y(#[easy_xml(text)] f64),
+}
+
+#[test]
+fn test() {
+    // 元组结构体和只有一个字段的结构体
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(root)]
+        struct Route {
+            #[easy_xml(attribute)]
+            ids: Ids,
+            #[easy_xml(rename = "Length")]
+            length: Meters,
+            #[easy_xml(rename = "Name")]
+            name: Name,
+            #[easy_xml(attribute)]
+            color: Option<Background>,
+        }
+        let xml = r##"<Route ids="1 2" color="#ff8000"><Length>12.5</Length></Route>"##;
+        let route: Route = de::from_str(xml).unwrap();
+        assert_eq!(
+            route,
+            Route {
+                ids: Ids(vec![1, 2]),
+                length: Meters(12.5),
+                name: Name { value: None },
+                color: Some(Background(Color(255, 128, 0))),
+            }
+        );
+
+        let xml = se::to_string(&route).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r##"<?xml version="1.0" encoding="UTF-8"?><Route ids="1 2" color="#ff8000"><Length>12.5</Length></Route>"##
+        );
+
+        // 非Option的字段缺少时仍然报错
+        let xml = r#"<Route ids=""></Route>"#;
+        assert!(de::from_str::<Route>(xml).is_err());
+    }
+    // 多个字段的元组结构体按子节点顺序匹配
+    {
+        let xml = r#"<Point label="a"><Y>2</Y><X>1</X></Point>"#;
+        let point: Point = de::from_str(xml).unwrap();
+        assert_eq!(
+            point,
+            Point(
+                Some("a".to_string()),
+                Coordinate::Y(2.0),
+                Coordinate::X(1.0)
+            )
+        );
+        let xml = se::to_string(&point).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><Point label="a"><Y>2</Y><X>1</X></Point>"#
+        );
+        assert!(de::from_str::<Point>(r#"<Point><X>1</X></Point>"#).is_err());
+    }
+    // 文本类型的元组字段没有节点名称，需要rename
+    {
+        #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+        #[easy_xml(rename = "P")]
+        struct Pair(
+            #[easy_xml(rename = "x")] f64,
+            #[easy_xml(rename = "y")] Option<String>,
+        );
+
+        let xml = r#"<P><x>1</x><y>2</y></P>"#;
+        let pair: Pair = de::from_str(xml).unwrap();
+        assert_eq!(pair, Pair(1.0, Some("2".to_string())));
+        let xml = se::to_string(&pair).unwrap();
+        assert_eq!(
+            xml.as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><P><x>1</x><y>2</y></P>"#
+        );
+    }
+    // 字段类型没有设置节点名称时序列化报错，而不是写出没有名称的节点
+    {
+        #[derive(Debug, PartialEq, XmlSerialize)]
+        #[easy_xml(rename = "Track")]
+        struct Track(Meters);
+
+        assert!(matches!(
+            se::to_string(&Track(Meters(1.0))),
+            Err(se::Error::UnnamedNode)
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+#[macro_use]
+extern crate easy_xml_derive;
+use std::time::Duration;
+
+use easy_xml::{de, se, XmlDeserialize, XmlElement, XmlSerialize};
+
+// 以秒为单位读写Duration
+mod seconds {
+    use std::time::Duration;
+
+    use easy_xml::{de, XmlDeserialize, XmlElement, XmlSerialize};
+
+    pub fn deserialize(element: &XmlElement) -> Result<Duration, de::Error> {
+        Ok(Duration::from_secs(u64::deserialize(element)?))
+    }
+
+    pub fn serialize(value: &Duration, element: &mut XmlElement) {
+        value.as_secs().serialize(element);
+    }
+}
+
+fn upper(element: &XmlElement) -> Result<String, de::Error> {
+    Ok(String::deserialize(element)?.to_uppercase())
+}
+
+fn lower(value: &str, element: &mut XmlElement) {
+    value.to_lowercase().serialize(element);
+}
+
+#[test]
+fn test() {
+    #[derive(Debug, PartialEq, XmlDeserialize, XmlSerialize)]
+    #[easy_xml(root)]
+    struct Task {
+        #[easy_xml(attribute, with = "seconds")]
+        timeout: Duration,
+        #[easy_xml(rename = "Retry", with = "seconds")]
+        retries: Vec<Duration>,
+        #[easy_xml(rename = "Delay", with = "seconds")]
+        delay: Option<Duration>,
+        #[easy_xml(rename = "Name", deserialize_with = "upper", serialize_with = "lower")]
+        name: String,
+        #[easy_xml(text(own), with = "seconds")]
+        total: Duration,
+    }
+
+    let xml = r#"<Task timeout="30"><Retry>1</Retry><Retry>5</Retry><Name>Build</Name>60</Task>"#;
+    let task: Task = de::from_str(xml).unwrap();
+    assert_eq!(
+        task,
+        Task {
+            timeout: Duration::from_secs(30),
+            retries: vec![Duration::from_secs(1), Duration::from_secs(5)],
+            delay: None,
+            name: "BUILD".to_string(),
+            total: Duration::from_secs(60),
+        }
+    );
+
+    let task = Task {
+        delay: Some(Duration::from_secs(2)),
+        ..task
+    };
+    let xml = se::to_string(&task).unwrap();
+    assert_eq!(
+        xml.as_str(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Task timeout="30">60<Retry>1</Retry><Retry>5</Retry><Delay>2</Delay><Name>build</Name></Task>"#
+    );
+
+    // 函数返回的错误
+    let xml = r#"<Task timeout="x"><Name>a</Name>1</Task>"#;
+    assert!(de::from_str::<Task>(xml).is_err());
+    // 缺少非Option字段时报错
+    let xml = r#"<Task><Name>a</Name>1</Task>"#;
+    assert!(de::from_str::<Task>(xml).is_err());
+}